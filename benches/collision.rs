@@ -0,0 +1,108 @@
+// Times one tick's worth of collision queries against 5000 enemies, with and
+// without the spatial grid, for a growing number of attacks and bullets. The
+// grid's cost is mostly the rebuild, which the game pays once per tick and
+// shares with enemy steering, so on its own it pays off once there are more
+// than a handful of queries. Run with `cargo bench --bench collision`.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use bevy::prelude::*;
+use bevy_hell::spatial::SpatialGrid;
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+const ENEMIES: u32 = 5000;
+const ATTACKS: [usize; 4] = [8, 32, 128, 512];
+const ATTACK_REACH: f32 = 50.;
+const CONTACT_REACH: f32 = 32.;
+const TICKS: u32 = 200;
+
+struct World {
+    enemies: Vec<(Entity, Vec2)>,
+    attacks: Vec<Vec2>,
+    player: Vec2,
+}
+
+impl World {
+    fn new(seed: u64, attacks: usize) -> Self {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        // Enemies crowd the screen around the player, as they do late in a run.
+        let mut point =
+            || Vec2::new(rng.gen_range(-800.0..800.), rng.gen_range(-800.0..800.));
+        Self {
+            enemies: (0..ENEMIES)
+                .map(|i| (Entity::from_raw(i), point()))
+                .collect(),
+            attacks: (0..attacks).map(|_| point()).collect(),
+            player: Vec2::ZERO,
+        }
+    }
+
+    fn queries(&self) -> impl Iterator<Item = (Vec2, f32)> + '_ {
+        self.attacks
+            .iter()
+            .map(|attack| (*attack, ATTACK_REACH))
+            .chain([(self.player, CONTACT_REACH)])
+    }
+}
+
+fn full_scan(world: &World) -> usize {
+    world
+        .queries()
+        .map(|(centre, reach)| {
+            world
+                .enemies
+                .iter()
+                .filter(|(_, enemy)| enemy.distance(centre) < reach)
+                .count()
+        })
+        .sum()
+}
+
+// Includes the rebuild, which the game pays once per tick.
+fn with_grid(world: &World, grid: &mut SpatialGrid) -> usize {
+    grid.rebuild(world.enemies.iter().copied());
+    let positions = &world.enemies;
+    world
+        .queries()
+        .map(|(centre, reach)| {
+            grid.entities_within(centre, reach)
+                .filter(|entity| {
+                    positions[entity.index() as usize].1.distance(centre) < reach
+                })
+                .count()
+        })
+        .sum()
+}
+
+fn time(mut tick: impl FnMut() -> usize) -> Duration {
+    let start = Instant::now();
+    for _ in 0..TICKS {
+        black_box(tick());
+    }
+    start.elapsed() / TICKS
+}
+
+fn main() {
+    println!("{ENEMIES} enemies, time per tick over {TICKS} ticks");
+    println!(
+        "{:>8} {:>12} {:>12} {:>8}",
+        "attacks", "full scan", "grid", "speedup"
+    );
+    for attacks in ATTACKS {
+        let world = World::new(0, attacks);
+        let mut grid = SpatialGrid::default();
+        assert_eq!(full_scan(&world), with_grid(&world, &mut grid));
+
+        let scan = time(|| full_scan(black_box(&world)));
+        let gridded = time(|| with_grid(black_box(&world), &mut grid));
+        println!(
+            "{attacks:>8} {:>12} {:>12} {:>7.1}x",
+            format!("{scan:.1?}"),
+            format!("{gridded:.1?}"),
+            scan.as_secs_f64() / gridded.as_secs_f64()
+        );
+    }
+}
@@ -1,49 +1,161 @@
-use bevy::prelude::*;
-
-#[derive(Resource)]
-pub struct Images {
-    pub samurai: Handle<Image>,
-    pub blob: Handle<Image>,
-    pub blob_death: Handle<Image>,
-    pub slash_attack: Handle<Image>,
-    pub health_potion: Handle<Image>,
-}
-
-#[derive(Resource)]
-pub struct Audio {
-    pub health_down: Handle<AudioSource>,
-    pub slash_attack: Handle<AudioSource>,
-    pub background_track: Handle<AudioSource>,
-}
-
-#[derive(Component)]
-pub struct PlayerHitSound {
-    pub timer: Timer,
-}
-
-pub struct AssetLoader;
-
-impl Plugin for AssetLoader {
-    fn build(&self, app: &mut App) {
-        app.add_systems(PreStartup, setup_images);
-        app.add_systems(PreStartup, setup_audio);
-    }
-}
-
-fn setup_images(mut commands: Commands, asset_server: Res<AssetServer>) {
-    commands.insert_resource(Images {
-        samurai: asset_server.load("samurai.png"),
-        blob: asset_server.load("blob.png"),
-        blob_death: asset_server.load("blob_death.png"),
-        slash_attack: asset_server.load("slash_attack.png"),
-        health_potion: asset_server.load("health_potion.png"),
-    });
-}
-
-fn setup_audio(mut commands: Commands, asset_server: Res<AssetServer>) {
-    commands.insert_resource(Audio {
-        health_down: asset_server.load("health_down.ogg"),
-        slash_attack: asset_server.load("slash_attack.ogg"),
-        background_track: asset_server.load("background_track.ogg"),
-    });
-}
+use std::{collections::HashMap, fmt, marker::PhantomData};
+
+use bevy::{
+    asset::{io::Reader, LoadContext},
+    prelude::*,
+};
+use serde::de::DeserializeOwned;
+
+use crate::{enemy::kinds::EnemyKinds, launch::no_audio, settings::Settings, RunScoped};
+
+#[derive(Resource, Default)]
+pub struct Images {
+    pub samurai: Handle<Image>,
+    pub slash_attack: Handle<Image>,
+    pub health_potion: Handle<Image>,
+    pub grass: Handle<Image>,
+    pub dirt: Handle<Image>,
+    pub tree: Handle<Image>,
+    pub props: Handle<Image>,
+    pub spit: Handle<Image>,
+    // Enemy sprite sheets, keyed by the path the enemy registry gives them.
+    pub enemy_sheets: HashMap<String, Handle<Image>>,
+}
+
+#[derive(Resource, Default)]
+pub struct Audio {
+    pub health_down: Handle<AudioSource>,
+    pub slash_attack: Handle<AudioSource>,
+    pub background_track: Handle<AudioSource>,
+}
+
+#[derive(Component)]
+pub struct BackgroundMusic;
+
+#[derive(Component)]
+#[require(RunScoped)]
+pub struct PlayerHitSound {
+    pub timer: Timer,
+}
+
+pub struct AssetLoader;
+
+impl Plugin for AssetLoader {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreStartup, setup_images);
+        app.add_systems(PreStartup, setup_audio);
+        app.add_systems(
+            Update,
+            load_enemy_sheets.run_if(resource_changed::<EnemyKinds>),
+        );
+        app.add_systems(Startup, mute_audio.run_if(no_audio));
+        app.add_systems(PostStartup, play_background_audio.run_if(not(no_audio)));
+    }
+}
+
+fn setup_images(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(Images {
+        samurai: asset_server.load("samurai.png"),
+        slash_attack: asset_server.load("slash_attack.png"),
+        health_potion: asset_server.load("health_potion.png"),
+        grass: asset_server.load("grass.png"),
+        dirt: asset_server.load("dirt.png"),
+        tree: asset_server.load("tree.png"),
+        props: asset_server.load("props.png"),
+        spit: asset_server.load("spit.png"),
+        enemy_sheets: HashMap::new(),
+    });
+}
+
+fn load_enemy_sheets(
+    asset_server: Res<AssetServer>,
+    kinds: Res<EnemyKinds>,
+    mut images: ResMut<Images>,
+) {
+    for enemy in &kinds.registry.kinds {
+        for sheet in [&enemy.sprite, &enemy.death] {
+            if !images.enemy_sheets.contains_key(&sheet.image) {
+                let handle = asset_server.load(sheet.image.clone());
+                images.enemy_sheets.insert(sheet.image.clone(), handle);
+            }
+        }
+    }
+}
+
+fn setup_audio(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(Audio {
+        health_down: asset_server.load("health_down.ogg"),
+        slash_attack: asset_server.load("slash_attack.ogg"),
+        background_track: asset_server.load("background_track.ogg"),
+    });
+}
+
+fn mute_audio(mut global_volume: ResMut<GlobalVolume>) {
+    *global_volume = GlobalVolume::new(0.);
+}
+
+fn play_background_audio(
+    mut commands: Commands,
+    audio: Res<Audio>,
+    settings: Res<Settings>,
+) {
+    commands.spawn((
+        AudioPlayer::<AudioSource>(audio.background_track.clone()),
+        PlaybackSettings::LOOP.with_volume(settings.music()),
+        BackgroundMusic,
+    ));
+}
+
+// Data assets written in RON, told apart by their file extensions.
+pub trait RonAsset: Asset + DeserializeOwned {
+    const EXTENSIONS: &'static [&'static str];
+}
+
+#[derive(Debug)]
+pub enum RonLoadError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+}
+
+impl fmt::Display for RonLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RonLoadError::Io(err) => write!(f, "could not read file: {err}"),
+            RonLoadError::Ron(err) => write!(f, "invalid RON: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for RonLoadError {}
+
+pub struct RonAssetLoader<A>(PhantomData<fn() -> A>);
+
+impl<A> Default for RonAssetLoader<A> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<A: RonAsset> bevy::asset::AssetLoader for RonAssetLoader<A> {
+    type Asset = A;
+    type Settings = ();
+    type Error = RonLoadError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(RonLoadError::Io)?;
+        ron::de::from_bytes(&bytes).map_err(RonLoadError::Ron)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        A::EXTENSIONS
+    }
+}
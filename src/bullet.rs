@@ -1,67 +1,130 @@
-use bevy::prelude::*;
-
-use crate::enemy::components::Enemy;
-
-#[derive(Component)]
-pub struct Bullet {
-    pub direction: Vec2,
-    pub lifetime: Timer,
-}
-
-#[allow(dead_code)]
-impl Bullet {
-    pub fn new(direction: Vec2) -> Self {
-        Self {
-            direction,
-            lifetime: Timer::from_seconds(3., TimerMode::Once),
-        }
-    }
-}
-
-pub struct BulletPlugin;
-
-impl Plugin for BulletPlugin {
-    fn build(&self, app: &mut App) {
-        app.add_systems(Update, (bullet_movement, bullet_lifetime, bullet_collision));
-    }
-}
-
-fn bullet_movement(
-    time: Res<Time>,
-    mut bullet_query: Query<(&mut Transform, &Bullet), With<Bullet>>,
-) {
-    for (mut transform, bullet) in bullet_query.iter_mut() {
-        transform.translation.x += bullet.direction.x * 400. * time.delta_secs();
-        transform.translation.y += bullet.direction.y * 400. * time.delta_secs();
-    }
-}
-
-fn bullet_lifetime(
-    mut commands: Commands,
-    mut bullet_query: Query<(Entity, &mut Bullet)>,
-    time: Res<Time>,
-) {
-    for (entity, mut bullet) in bullet_query.iter_mut() {
-        bullet.lifetime.tick(time.delta());
-        if bullet.lifetime.finished() {
-            commands.entity(entity).despawn();
-        }
-    }
-}
-
-fn bullet_collision(
-    mut bullet_query: Query<&Transform, (With<Bullet>, Without<Enemy>)>,
-    mut enemy_query: Query<(&mut Enemy, &Transform), (With<Enemy>, Without<Bullet>)>,
-) {
-    for bullet_transform in bullet_query.iter_mut() {
-        for (mut enemy, enemy_transform) in enemy_query.iter_mut() {
-            if bullet_transform
-                .translation
-                .distance(enemy_transform.translation)
-                < 32.
-            {
-                enemy.receive_damage(10.);
-            }
-        }
-    }
-}
+use bevy::prelude::*;
+
+use crate::{
+    enemy::components::Enemy,
+    interpolation::Interpolated,
+    launch::god_mode,
+    player::components::{Player, PLAYER_RADIUS},
+    spatial::SpatialGrid,
+    CollisionSet, GameState, MovementSet, RunScoped,
+};
+
+const BULLET_RADIUS: f32 = 6.;
+
+// Who fired a bullet, and so who it can hit. Player bullets hit enemies, enemy
+// bullets hit the player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Faction {
+    Player,
+    Enemy,
+}
+
+#[derive(Component)]
+#[require(Interpolated, RunScoped)]
+pub struct Bullet {
+    pub direction: Vec2,
+    pub speed: f32,
+    pub damage: f32,
+    pub faction: Faction,
+    pub lifetime: Timer,
+}
+
+impl Bullet {
+    pub fn new(direction: Vec2, speed: f32, damage: f32, faction: Faction) -> Self {
+        Self {
+            direction,
+            speed,
+            damage,
+            faction,
+            lifetime: Timer::from_seconds(3., TimerMode::Once),
+        }
+    }
+}
+
+pub struct BulletPlugin;
+
+impl Plugin for BulletPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (
+                bullet_movement.in_set(MovementSet),
+                bullet_lifetime.in_set(MovementSet),
+                bullet_collision.in_set(CollisionSet),
+                hostile_bullet_collision
+                    .in_set(CollisionSet)
+                    .run_if(not(god_mode)),
+            )
+                .run_if(in_state(GameState::Running)),
+        );
+    }
+}
+
+fn bullet_movement(time: Res<Time>, mut bullet_query: Query<(&mut Transform, &Bullet)>) {
+    for (mut transform, bullet) in bullet_query.iter_mut() {
+        let step = bullet.direction * bullet.speed * time.delta_secs();
+        transform.translation += step.extend(0.);
+    }
+}
+
+fn bullet_lifetime(
+    mut commands: Commands,
+    mut bullet_query: Query<(Entity, &mut Bullet)>,
+    time: Res<Time>,
+) {
+    for (entity, mut bullet) in bullet_query.iter_mut() {
+        bullet.lifetime.tick(time.delta());
+        if bullet.lifetime.finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+// Bullets are spent on the first thing they hit.
+fn bullet_collision(
+    mut commands: Commands,
+    bullet_query: Query<(Entity, &Transform, &Bullet), Without<Enemy>>,
+    mut enemy_query: Query<(&mut Enemy, &Transform), Without<Bullet>>,
+    grid: Res<SpatialGrid>,
+) {
+    let bullets = bullet_query
+        .iter()
+        .filter(|(_, _, bullet)| bullet.faction == Faction::Player);
+    for (entity, bullet_transform, bullet) in bullets {
+        let position = bullet_transform.translation;
+        for target in grid.entities_within(position.truncate(), 32.) {
+            let Ok((mut enemy, enemy_transform)) = enemy_query.get_mut(target) else {
+                continue;
+            };
+            if enemy.health > 0. && position.distance(enemy_transform.translation) < 32. {
+                enemy.receive_damage(bullet.damage);
+                commands.entity(entity).despawn();
+                break;
+            }
+        }
+    }
+}
+
+fn hostile_bullet_collision(
+    mut commands: Commands,
+    bullet_query: Query<(Entity, &Transform, &Bullet), Without<Player>>,
+    mut player_query: Query<(&mut Player, &Transform), Without<Bullet>>,
+    time: Res<Time>,
+) {
+    let Ok((mut player, player_transform)) = player_query.get_single_mut() else {
+        return;
+    };
+
+    let target = player_transform.translation.truncate();
+    let bullets = bullet_query
+        .iter()
+        .filter(|(_, _, bullet)| bullet.faction == Faction::Enemy);
+    for (entity, bullet_transform, bullet) in bullets {
+        let distance = bullet_transform.translation.truncate().distance(target);
+        if distance < PLAYER_RADIUS + BULLET_RADIUS {
+            player.receive_damage(bullet.damage);
+            player.last_damage = time.elapsed_secs_f64();
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -1,37 +1,66 @@
-use bevy::prelude::*;
-
-use crate::player::components::Player;
-
-#[derive(Component)]
-pub struct GameCamera;
-
-pub struct CameraPlugin;
-
-impl Plugin for CameraPlugin {
-    fn build(&self, app: &mut App) {
-        app.add_systems(Startup, setup)
-            .add_systems(PostUpdate, move_camera);
-    }
-}
-
-fn setup(mut commands: Commands) {
-    commands.spawn((Camera2d, GameCamera));
-}
-
-fn move_camera(
-    mut camera_query: Query<&mut Transform, (With<GameCamera>, Without<Player>)>,
-    player_query: Query<&Transform, (With<Player>, Without<GameCamera>)>,
-) {
-    let Ok(player_transform) = player_query.get_single() else {
-        return;
-    };
-    let Ok(mut camera_transform) = camera_query.get_single_mut() else {
-        return;
-    };
-
-    camera_transform.translation = camera_transform
-        .translation
-        .truncate()
-        .lerp(player_transform.translation.truncate(), 1.)
-        .extend(999.);
-}
+use bevy::prelude::*;
+
+use crate::{map::ActiveMap, player::components::Player};
+
+#[derive(Component)]
+pub struct GameCamera;
+
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ClearColor(Color::srgb_u8(1, 50, 45)))
+            .add_systems(Startup, setup)
+            .add_systems(PostUpdate, move_camera);
+    }
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn((Camera2d, GameCamera));
+}
+
+fn move_camera(
+    mut camera_query: Query<
+        (&mut Transform, &OrthographicProjection),
+        (With<GameCamera>, Without<Player>),
+    >,
+    player_query: Query<&Transform, (With<Player>, Without<GameCamera>)>,
+    map: Res<ActiveMap>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let Ok((mut camera_transform, projection)) = camera_query.get_single_mut() else {
+        return;
+    };
+
+    let target = camera_transform
+        .translation
+        .truncate()
+        .lerp(player_transform.translation.truncate(), 1.);
+    let target = match map.definition.bounds {
+        Some(bounds) => clamp_view(target, projection.area.half_size(), bounds),
+        None => target,
+    };
+    camera_transform.translation = target.extend(999.);
+}
+
+// Keeps the view inside the arena, centring it on any axis where the arena is
+// smaller than the screen.
+fn clamp_view(centre: Vec2, half_view: Vec2, bounds: Rect) -> Vec2 {
+    let min = bounds.min + half_view;
+    let max = bounds.max - half_view;
+    let middle = bounds.center();
+    Vec2::new(
+        if min.x <= max.x {
+            centre.x.clamp(min.x, max.x)
+        } else {
+            middle.x
+        },
+        if min.y <= max.y {
+            centre.y.clamp(min.y, max.y)
+        } else {
+            middle.y
+        },
+    )
+}
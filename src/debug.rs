@@ -1,7 +1,9 @@
 use bevy::{color, prelude::*};
 use sysinfo::System;
 
-use crate::{enemy::components::Enemy, player::components::Player};
+use crate::{
+    enemy::components::Enemy, launch::debug_overlay, player::components::Player,
+};
 
 #[derive(Component)]
 pub struct DebugText;
@@ -15,9 +17,12 @@ pub struct DebugPlugin;
 
 impl Plugin for DebugPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, init_system_info)
-            .add_systems(PostStartup, build_debug_text)
-            .add_systems(Update, update_debug_text);
+        app.add_systems(Startup, init_system_info.run_if(debug_overlay))
+            .add_systems(PostStartup, build_debug_text.run_if(debug_overlay))
+            .add_systems(
+                Update,
+                update_debug_text.run_if(resource_exists::<SystemInfo>),
+            );
     }
 }
 
@@ -0,0 +1,144 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    components::{Boss, Enemy, Reload},
+    kinds::{AttackPattern, EnemyKind, EnemyKinds},
+    systems::enemy_bundle,
+    waves::Waves,
+};
+use crate::{
+    assets::Images,
+    bullet::{Bullet, Faction},
+    map::ActiveMap,
+    player::components::Player,
+    rng::{GameRng, RngStream},
+    GlobalStopwatch,
+};
+
+// A boss that turns up when the run clock passes `at`, listed with the waves in
+// the wave schedule. Resuming a save past a mark does not bring that boss back.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BossEncounter {
+    // Seconds into the run.
+    pub at: f32,
+    pub kind: String,
+}
+
+pub fn spawn_bosses(
+    mut commands: Commands,
+    player_query: Query<&Transform, With<Player>>,
+    waves: Res<Waves>,
+    kinds: Res<EnemyKinds>,
+    icons: Res<Images>,
+    watch: Res<GlobalStopwatch>,
+    time: Res<Time>,
+    mut rng: ResMut<GameRng>,
+    map: Res<ActiveMap>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+
+    let now = watch.clock.elapsed_secs();
+    let before = now - time.delta_secs();
+    for encounter in &waves.schedule.bosses {
+        if encounter.at <= before || encounter.at > now {
+            continue;
+        }
+        let Some(kind) = kinds.registry.find(&encounter.kind) else {
+            warn!(
+                "no enemy kind {:?} for the boss at {}s",
+                encounter.kind, encounter.at
+            );
+            continue;
+        };
+
+        let position = map.definition.spawn_point(
+            rng.stream(RngStream::EnemySpawn),
+            player_transform.translation.truncate(),
+        );
+        commands.spawn((
+            enemy_bundle(
+                &kinds,
+                &icons,
+                kind,
+                position.extend(1.),
+                kinds.get(kind).health,
+            ),
+            Boss,
+        ));
+    }
+}
+
+// Bosses fire the pattern of whichever phase their health puts them in.
+pub fn boss_attack(
+    mut commands: Commands,
+    mut boss_query: Query<(&Transform, &Enemy, &EnemyKind, &mut Reload), With<Boss>>,
+    player_query: Query<&Transform, (With<Player>, Without<Boss>)>,
+    kinds: Res<EnemyKinds>,
+    images: Res<Images>,
+    watch: Res<GlobalStopwatch>,
+    time: Res<Time>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+
+    let target = player_transform.translation.truncate();
+    for (transform, enemy, kind, mut reload) in boss_query.iter_mut() {
+        let enemy_type = kinds.get(*kind);
+        let (Some(attack), Some(phase)) =
+            (&enemy_type.boss, enemy_type.phase(enemy.health))
+        else {
+            continue;
+        };
+        reload.0.tick(time.delta());
+
+        let position = transform.translation.truncate();
+        if enemy.health <= 0.
+            || !reload.0.finished()
+            || position.distance(target) > attack.range
+        {
+            continue;
+        }
+
+        reload.0 = Timer::from_seconds(phase.cooldown, TimerMode::Once);
+        let aim = (target - position).to_angle();
+        let angles: Vec<f32> = match phase.pattern {
+            AttackPattern::Fan { count, spread } => (0..count)
+                .map(|i| {
+                    let step = if count > 1 {
+                        i as f32 / (count - 1) as f32
+                    } else {
+                        0.5
+                    };
+                    aim + (step - 0.5) * spread
+                })
+                .collect(),
+            AttackPattern::Ring { count } => (0..count)
+                .map(|i| aim + i as f32 / count as f32 * TAU)
+                .collect(),
+            AttackPattern::Spiral { arms, turn } => {
+                let start = watch.clock.elapsed_secs() * turn;
+                (0..arms)
+                    .map(|i| start + i as f32 / arms as f32 * TAU)
+                    .collect()
+            }
+        };
+        for angle in angles {
+            commands.spawn((
+                Sprite::from_image(images.spit.clone()),
+                Transform::from_translation(position.extend(1.)),
+                Bullet::new(
+                    Vec2::from_angle(angle),
+                    attack.projectile_speed,
+                    attack.damage,
+                    Faction::Enemy,
+                ),
+            ));
+        }
+    }
+}
@@ -1,29 +1,83 @@
 use bevy::prelude::*;
-use rand::rngs::SmallRng;
-use rand::{Rng, SeedableRng};
+use rand::Rng;
+
+use super::{kinds::EnemyKind, steering::Heading};
+use crate::{interpolation::Interpolated, RunScoped};
+
+pub const ENEMY_RADIUS: f32 = 12.;
+// From the player's centre to the centre of an enemy touching it.
+pub const CONTACT_REACH: f32 = 32.;
+// How quickly knockback fades, as a fraction of its speed lost a second.
+const KNOCKBACK_DECAY: f32 = 8.;
+// Enemies knocked back faster than this cannot move or shoot on their own.
+const KNOCKBACK_RECOVERY: f32 = 40.;
 
 #[derive(Component)]
+#[require(Interpolated, RunScoped, EnemyKind, Heading, Reload, Knockback)]
 pub struct Enemy {
     pub health: f32,
     pub last_damage: f64,
 }
 
+// `reach`, measured to the centre of an enemy of scale 1, widened or narrowed to
+// the body of an enemy drawn at `scale`.
+pub fn reach_for_scale(reach: f32, scale: f32) -> f32 {
+    reach + ENEMY_RADIUS * (scale - 1.)
+}
+
 impl Enemy {
     pub fn receive_damage(&mut self, damage: f32) {
         self.health -= damage;
     }
 }
 
+// The speed and direction an enemy was sent flying in by a hit, which fades
+// over a fraction of a second.
+#[derive(Component, Default)]
+pub struct Knockback(pub Vec2);
+
+impl Knockback {
+    // Heavier kinds are pushed back less by the same hit. A fresh hit replaces
+    // what is left of the last one rather than adding to it.
+    pub fn hit(&mut self, direction: Vec2, strength: f32, weight: f32) {
+        self.0 = direction.normalize_or_zero() * strength / weight.max(0.01);
+    }
+
+    pub fn staggered(&self) -> bool {
+        self.0.length() > KNOCKBACK_RECOVERY
+    }
+
+    pub fn decay(&mut self, delta: f32) {
+        self.0 *= (-KNOCKBACK_DECAY * delta).exp();
+        if self.0.length() < 1. {
+            self.0 = Vec2::ZERO;
+        }
+    }
+}
+
+// An enemy spawned by the boss schedule rather than the regular waves.
+#[derive(Component)]
+pub struct Boss;
+
+// Time until a ranged enemy or boss can shoot again. It starts out ready.
+#[derive(Component, Default)]
+pub struct Reload(pub Timer);
+
+// One countdown to the next spawn for each wave in the schedule, which only
+// runs while its wave is active.
 #[derive(Resource)]
 pub struct SpawnTimer {
-    pub countdown: Timer,
+    pub countdowns: Vec<Timer>,
 }
 
 impl SpawnTimer {
-    pub fn new() -> Self {
-        let mut rng = SmallRng::from_entropy();
+    pub fn new(rng: &mut impl Rng, waves: usize) -> Self {
         Self {
-            countdown: Timer::from_seconds(rng.gen_range(0.5..2.), TimerMode::Repeating),
+            countdowns: (0..waves)
+                .map(|_| {
+                    Timer::from_seconds(rng.gen_range(0.5..2.), TimerMode::Repeating)
+                })
+                .collect(),
         }
     }
 }
@@ -40,3 +94,9 @@ impl AttackTimer {
         }
     }
 }
+
+impl Default for AttackTimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,256 @@
+use std::f32::consts::TAU;
+
+use bevy::{
+    ecs::{component::ComponentId, world::DeferredWorld},
+    prelude::*,
+};
+use rand::{rngs::SmallRng, seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    components::Enemy,
+    kinds::{EnemyKind, EnemyKinds},
+    systems::enemy_bundle,
+};
+use crate::{
+    assets::Images,
+    pickups::pickup_bundle,
+    player::components::Player,
+    rng::{GameRng, RngStream},
+};
+
+// The chance of an elite grows by this much a minute, up to `MAX_ELITE_CHANCE`.
+const ELITE_CHANCE_PER_MINUTE: f64 = 0.03;
+const MAX_ELITE_CHANCE: f64 = 0.3;
+// Elites get another affix every this many seconds, up to `MAX_AFFIXES`.
+const AFFIX_EVERY: f64 = 300.;
+const MAX_AFFIXES: usize = 3;
+const ELITE_SCALE: f32 = 1.25;
+const DROP_CHANCE_PER_AFFIX: f32 = 0.25;
+const REGENERATION_PER_SECOND: f32 = 0.05;
+const REGENERATION_DELAY: f64 = 1.;
+const VAMPIRIC_HEALING: f32 = 2.;
+const EXPLOSION_RADIUS: f32 = 80.;
+const EXPLOSION_DAMAGE: f32 = 10.;
+const SPLITS: u32 = 2;
+const SPLIT_SCALE: f32 = 0.7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Affix {
+    Fast,
+    Armored,
+    Regenerating,
+    Vampiric,
+    Explosive,
+    Splitting,
+}
+
+impl Affix {
+    pub const ALL: [Affix; 6] = [
+        Affix::Fast,
+        Affix::Armored,
+        Affix::Regenerating,
+        Affix::Vampiric,
+        Affix::Explosive,
+        Affix::Splitting,
+    ];
+
+    // Seconds into the run before elites can roll this affix.
+    pub fn unlocked_at(self) -> f64 {
+        match self {
+            Affix::Fast | Affix::Armored => 0.,
+            Affix::Regenerating => 60.,
+            Affix::Vampiric => 120.,
+            Affix::Explosive => 180.,
+            Affix::Splitting => 240.,
+        }
+    }
+
+    fn tint(self) -> LinearRgba {
+        match self {
+            Affix::Fast => LinearRgba::rgb(0.5, 0.8, 1.),
+            Affix::Armored => LinearRgba::rgb(0.6, 0.6, 0.7),
+            Affix::Regenerating => LinearRgba::rgb(0.4, 1., 0.4),
+            Affix::Vampiric => LinearRgba::rgb(1., 0.25, 0.3),
+            Affix::Explosive => LinearRgba::rgb(1., 0.55, 0.1),
+            Affix::Splitting => LinearRgba::rgb(0.9, 0.4, 1.),
+        }
+    }
+}
+
+// An enemy with one or more affixes. Adding it tints and enlarges the sprite.
+#[derive(Component, Debug, Clone, PartialEq)]
+#[component(on_add = mark_elite)]
+pub struct Elite {
+    pub affixes: Vec<Affix>,
+}
+
+fn mark_elite(mut world: DeferredWorld, entity: Entity, _: ComponentId) {
+    let Some(elite) = world.get::<Elite>(entity).cloned() else {
+        return;
+    };
+    if let Some(mut sprite) = world.get_mut::<Sprite>(entity) {
+        sprite.color = elite.tinted(sprite.color);
+    }
+    if let Some(mut transform) = world.get_mut::<Transform>(entity) {
+        transform.scale *= ELITE_SCALE;
+    }
+}
+
+impl Elite {
+    // Rolls whether an enemy spawned `elapsed` seconds into the run is an
+    // elite, and with which affixes.
+    pub fn roll(elapsed: f64, rng: &mut SmallRng) -> Option<Self> {
+        let chance = (elapsed / 60. * ELITE_CHANCE_PER_MINUTE).min(MAX_ELITE_CHANCE);
+        if rng.gen::<f64>() >= chance {
+            return None;
+        }
+        let pool: Vec<Affix> = Affix::ALL
+            .into_iter()
+            .filter(|affix| affix.unlocked_at() <= elapsed)
+            .collect();
+        let count = (1 + (elapsed / AFFIX_EVERY) as usize).min(MAX_AFFIXES);
+        let mut affixes: Vec<Affix> = pool.choose_multiple(rng, count).copied().collect();
+        affixes.sort_by_key(|affix| *affix as u8);
+        Some(Self { affixes })
+    }
+
+    pub fn has(&self, affix: Affix) -> bool {
+        self.affixes.contains(&affix)
+    }
+
+    pub fn speed(&self) -> f32 {
+        if self.has(Affix::Fast) {
+            1.5
+        } else {
+            1.
+        }
+    }
+
+    pub fn health(&self, base: f32) -> f32 {
+        if self.has(Affix::Armored) {
+            base * 2.
+        } else {
+            base
+        }
+    }
+
+    // Each affix adds the kind's XP again.
+    pub fn xp(&self, base: u32) -> u32 {
+        base * (1 + self.affixes.len() as u32)
+    }
+
+    // Vampiric elites heal by some multiple of the contact damage they deal.
+    pub fn feed(&self, enemy: &mut Enemy, damage: f32, base_health: f32) {
+        if self.has(Affix::Vampiric) {
+            enemy.health =
+                (enemy.health + damage * VAMPIRIC_HEALING).min(self.health(base_health));
+        }
+    }
+
+    // `color` shaded by the average tint of the affixes.
+    pub fn tinted(&self, color: Color) -> Color {
+        let sum = self
+            .affixes
+            .iter()
+            .fold(LinearRgba::NONE, |sum, affix| sum + affix.tint());
+        let tint = (sum * (1. / self.affixes.len().max(1) as f32)).with_alpha(1.);
+        LinearRgba::from_vec4(color.to_linear().to_vec4() * tint.to_vec4()).into()
+    }
+}
+
+pub fn regenerate_elites(
+    mut elite_query: Query<(&mut Enemy, &EnemyKind, &Elite)>,
+    kinds: Res<EnemyKinds>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs_f64();
+    for (mut enemy, kind, elite) in elite_query.iter_mut() {
+        if !elite.has(Affix::Regenerating)
+            || enemy.health <= 0.
+            || now - enemy.last_damage < REGENERATION_DELAY
+        {
+            continue;
+        }
+        let max_health = elite.health(kinds.get(*kind).health);
+        enemy.health = (enemy.health
+            + max_health * REGENERATION_PER_SECOND * time.delta_secs())
+        .min(max_health);
+    }
+}
+
+// What elites leave behind, on top of what every enemy does when it dies.
+pub fn elite_deaths(
+    mut commands: Commands,
+    elite_query: Query<(&Enemy, &EnemyKind, &Elite, &Transform)>,
+    mut player_query: Query<&mut Player>,
+    kinds: Res<EnemyKinds>,
+    icons: Res<Images>,
+    mut rng: ResMut<GameRng>,
+) {
+    let Ok(mut player) = player_query.get_single_mut() else {
+        return;
+    };
+
+    let rng = rng.stream(RngStream::Elites);
+    for (enemy, kind, elite, transform) in elite_query.iter() {
+        if enemy.health > 0. {
+            continue;
+        }
+
+        let enemy_type = kinds.get(*kind);
+        player.gain_xp(elite.xp(enemy_type.xp) - enemy_type.xp);
+
+        let position = transform.translation;
+        let chance = DROP_CHANCE_PER_AFFIX * elite.affixes.len() as f32;
+        if rng.gen::<f32>() < chance {
+            commands.spawn(pickup_bundle(
+                icons.health_potion.clone(),
+                position.with_z(1.),
+            ));
+        }
+
+        if elite.has(Affix::Splitting) {
+            let start = rng.gen_range(0.0..TAU);
+            for i in 0..SPLITS {
+                let offset =
+                    Vec2::from_angle(start + i as f32 / SPLITS as f32 * TAU) * 12.;
+                let translation = position + offset.extend(0.);
+                commands
+                    .spawn(enemy_bundle(
+                        &kinds,
+                        &icons,
+                        *kind,
+                        translation,
+                        enemy_type.health / 2.,
+                    ))
+                    .insert(
+                        Transform::from_translation(translation)
+                            .with_scale(Vec3::splat(enemy_type.scale * SPLIT_SCALE)),
+                    );
+            }
+        }
+    }
+}
+
+// Explosive elites hurt the player if it is close when they die.
+pub fn elite_explosions(
+    elite_query: Query<(&Enemy, &Elite, &Transform)>,
+    mut player_query: Query<(&mut Player, &Transform), Without<Enemy>>,
+    time: Res<Time>,
+) {
+    let Ok((mut player, player_transform)) = player_query.get_single_mut() else {
+        return;
+    };
+
+    let target = player_transform.translation.truncate();
+    for (enemy, elite, transform) in elite_query.iter() {
+        if enemy.health <= 0.
+            && elite.has(Affix::Explosive)
+            && transform.translation.truncate().distance(target) < EXPLOSION_RADIUS
+        {
+            player.receive_damage(EXPLOSION_DAMAGE);
+            player.last_damage = time.elapsed_secs_f64();
+        }
+    }
+}
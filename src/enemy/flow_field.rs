@@ -0,0 +1,202 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use bevy::prelude::*;
+
+use crate::{map::trees::Trees, player::components::Player};
+
+// In tiles either side of the player, which covers the ring enemies spawn in.
+const FIELD_RADIUS: i32 = 64;
+const REFRESH_SECS: f32 = 0.5;
+// Enemies this many tiles or fewer from an obstacle follow the field, the rest
+// head straight for the player.
+const OBSTACLE_REACH: i32 = 2;
+const STRAIGHT_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+const NEIGHBOURS: [IVec2; 8] = [
+    IVec2::new(1, 0),
+    IVec2::new(-1, 0),
+    IVec2::new(0, 1),
+    IVec2::new(0, -1),
+    IVec2::new(1, 1),
+    IVec2::new(-1, 1),
+    IVec2::new(1, -1),
+    IVec2::new(-1, -1),
+];
+
+// Shortest paths to the player's tile around trees and walls, from every tile
+// in a square around it. Rebuilt a couple of times a second rather than every
+// tick, since the player rarely gets more than a few tiles from where it was.
+#[derive(Resource)]
+pub struct FlowField {
+    pub refresh: Timer,
+    origin: IVec2,
+    tile_size: f32,
+    side: i32,
+    // Path cost to the origin, or `u32::MAX` for tiles that cannot reach it.
+    costs: Vec<u32>,
+    directions: Vec<Vec2>,
+    near_obstacle: Vec<bool>,
+}
+
+impl Default for FlowField {
+    fn default() -> Self {
+        Self {
+            refresh: Timer::from_seconds(REFRESH_SECS, TimerMode::Repeating),
+            origin: IVec2::ZERO,
+            tile_size: 1.,
+            side: 0,
+            costs: Vec::new(),
+            directions: Vec::new(),
+            near_obstacle: Vec::new(),
+        }
+    }
+}
+
+impl FlowField {
+    pub fn origin(&self) -> IVec2 {
+        self.origin
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.costs.is_empty()
+    }
+
+    fn index(&self, tile: IVec2) -> Option<usize> {
+        let local = tile - self.origin + FIELD_RADIUS;
+        if local.min_element() < 0 || local.max_element() >= self.side {
+            return None;
+        }
+        Some((local.y * self.side + local.x) as usize)
+    }
+
+    fn tile(&self, index: usize) -> IVec2 {
+        let index = index as i32;
+        IVec2::new(index % self.side, index / self.side) + self.origin - FIELD_RADIUS
+    }
+
+    // Steps from `tile` that do not enter a blocked tile or cut the corner of
+    // one.
+    fn moves(
+        tile: IVec2,
+        blocked: impl Fn(IVec2) -> bool,
+    ) -> impl Iterator<Item = (IVec2, u32)> {
+        NEIGHBOURS.into_iter().filter_map(move |step| {
+            let next = tile + step;
+            if blocked(next) {
+                return None;
+            }
+            if step.x != 0 && step.y != 0 {
+                if blocked(tile + IVec2::new(step.x, 0))
+                    || blocked(tile + IVec2::new(0, step.y))
+                {
+                    return None;
+                }
+                return Some((next, DIAGONAL_COST));
+            }
+            Some((next, STRAIGHT_COST))
+        })
+    }
+
+    pub fn build(
+        &mut self,
+        origin: IVec2,
+        tile_size: f32,
+        blocked: impl Fn(IVec2) -> bool,
+    ) {
+        self.origin = origin;
+        self.tile_size = tile_size;
+        self.side = FIELD_RADIUS * 2 + 1;
+        let len = (self.side * self.side) as usize;
+
+        let mask: Vec<bool> = (0..len).map(|i| blocked(self.tile(i))).collect();
+        let is_blocked = |tile: IVec2| self.index(tile).is_none_or(|index| mask[index]);
+
+        let mut near_obstacle = vec![false; len];
+        for (i, near) in near_obstacle.iter_mut().enumerate() {
+            let tile = self.tile(i);
+            *near = (-OBSTACLE_REACH..=OBSTACLE_REACH).any(|y| {
+                (-OBSTACLE_REACH..=OBSTACLE_REACH)
+                    .any(|x| self.index(tile + IVec2::new(x, y)).is_some_and(|j| mask[j]))
+            });
+        }
+
+        // Dijkstra out from the origin. Ties pop in index order, so the field
+        // only depends on the map and where the player stands.
+        let mut costs = vec![u32::MAX; len];
+        let mut queue = BinaryHeap::new();
+        let start = self.index(origin).unwrap();
+        costs[start] = 0;
+        queue.push(Reverse((0, start)));
+        while let Some(Reverse((cost, index))) = queue.pop() {
+            if cost > costs[index] {
+                continue;
+            }
+            for (next, step) in Self::moves(self.tile(index), is_blocked) {
+                let next = self.index(next).unwrap();
+                if cost + step < costs[next] {
+                    costs[next] = cost + step;
+                    queue.push(Reverse((cost + step, next)));
+                }
+            }
+        }
+
+        // Each tile points at its cheapest neighbour. The origin and tiles
+        // that cannot reach it have no direction.
+        let directions = (0..len)
+            .map(|i| {
+                if costs[i] == 0 || costs[i] == u32::MAX {
+                    return Vec2::ZERO;
+                }
+                let tile = self.tile(i);
+                Self::moves(tile, is_blocked)
+                    .min_by_key(|(next, step)| {
+                        costs[self.index(*next).unwrap()].saturating_add(*step)
+                    })
+                    .map_or(Vec2::ZERO, |(next, _)| (next - tile).as_vec2().normalize())
+            })
+            .collect();
+
+        self.costs = costs;
+        self.directions = directions;
+        self.near_obstacle = near_obstacle;
+    }
+
+    pub fn cost(&self, tile: IVec2) -> Option<u32> {
+        let cost = self.costs[self.index(tile)?];
+        (cost != u32::MAX).then_some(cost)
+    }
+
+    // The way to go from `position`, if it is close enough to an obstacle that
+    // heading straight for the player might get it stuck.
+    pub fn heading(&self, position: Vec2) -> Option<Vec2> {
+        let tile = (position / self.tile_size).floor().as_ivec2();
+        let index = self.index(tile)?;
+        if !self.near_obstacle[index] {
+            return None;
+        }
+        Some(self.directions[index]).filter(|direction| *direction != Vec2::ZERO)
+    }
+}
+
+pub fn reset_flow_field(mut field: ResMut<FlowField>) {
+    *field = FlowField::default();
+}
+
+pub fn update_flow_field(
+    mut field: ResMut<FlowField>,
+    player_query: Query<&Transform, With<Player>>,
+    trees: Trees,
+    time: Res<Time>,
+) {
+    field.refresh.tick(time.delta());
+    if !field.refresh.just_finished() && !field.is_empty() {
+        return;
+    }
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+
+    let origin = trees.tile_at(player_transform.translation.truncate());
+    let tile_size = trees.tile_size();
+    field.build(origin, tile_size, |tile| trees.blocked(tile));
+}
@@ -0,0 +1,391 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    animation::AnimationIndices,
+    assets::{Images, RonAsset},
+    map::biomes::{pick_weighted, Biome},
+    LoadingAssets,
+};
+
+pub const ENEMY_REGISTRY: &str = "enemies/default.enemies.ron";
+
+// A single row of equally sized frames. Animations play from `first` to the
+// last frame.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpriteSheet {
+    pub image: String,
+    pub frame_size: UVec2,
+    pub frames: u32,
+    #[serde(default)]
+    pub first: u32,
+}
+
+impl SpriteSheet {
+    fn validate(&self) -> Result<(), String> {
+        if self.frames == 0 || self.frame_size.cmpeq(UVec2::ZERO).any() {
+            return Err(format!("sheet {:?} has no frames to show", self.image));
+        }
+        Ok(())
+    }
+
+    fn layout(&self) -> TextureAtlasLayout {
+        TextureAtlasLayout::from_grid(self.frame_size, self.frames, 1, None, None)
+    }
+
+    pub fn indices(&self) -> AnimationIndices {
+        let last = self.frames.saturating_sub(1) as usize;
+        let first = (self.first as usize).min(last);
+        AnimationIndices {
+            first,
+            last,
+            current: first,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnemyType {
+    pub name: String,
+    pub sprite: SpriteSheet,
+    pub death: SpriteSheet,
+    pub health: f32,
+    // Relative to `BASE_MOVE_SPEED`.
+    pub speed: f32,
+    pub contact_damage: f32,
+    pub xp: u32,
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+    // Resistance to knockback. Hits push a kind twice as heavy half as far.
+    #[serde(default = "default_scale")]
+    pub weight: f32,
+    #[serde(default = "default_tint")]
+    pub tint: Srgba,
+    // How often this kind spawns in each biome, against the other kinds there.
+    // Kinds without a weight for a biome never spawn in it on their own.
+    #[serde(default)]
+    pub spawn_weights: HashMap<Biome, u32>,
+    // Kinds with a ranged attack hang back at `range` and shoot instead of
+    // closing in.
+    #[serde(default)]
+    pub ranged: Option<RangedAttack>,
+    // Bosses never spawn from the weights, only from the boss schedule.
+    #[serde(default)]
+    pub boss: Option<BossAttack>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RangedAttack {
+    pub range: f32,
+    pub cooldown: f32,
+    pub projectile_speed: f32,
+    pub damage: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AttackPattern {
+    // A spread of `count` bullets aimed at the player.
+    Fan { count: u32, spread: f32 },
+    // `count` bullets in every direction at once.
+    Ring { count: u32 },
+    // `arms` bullets evenly apart, turning by `turn` radians a second.
+    Spiral { arms: u32, turn: f32 },
+}
+
+// One stage of a boss fight, which lasts while its health is at or below
+// `below` of the full amount and above the next phase's.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BossPhase {
+    pub below: f32,
+    #[serde(default = "default_scale")]
+    pub speed: f32,
+    pub pattern: AttackPattern,
+    pub cooldown: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BossAttack {
+    pub phases: Vec<BossPhase>,
+    pub range: f32,
+    pub projectile_speed: f32,
+    pub damage: f32,
+    // Health potions always dropped on death.
+    pub drops: u32,
+}
+
+impl EnemyType {
+    fn validate(&self) -> Result<(), String> {
+        if !self.health.is_finite() || self.health <= 0. {
+            return Err("health is not a positive number".into());
+        }
+        self.sprite.validate()?;
+        self.death.validate()?;
+        if let Some(ranged) = &self.ranged {
+            if !ranged.cooldown.is_finite() || ranged.cooldown < 0. {
+                return Err("the ranged cooldown is negative or not finite".into());
+            }
+        }
+        let phases = self.boss.iter().flat_map(|boss| &boss.phases);
+        for phase in phases {
+            if !phase.cooldown.is_finite() || phase.cooldown < 0. {
+                return Err("a boss phase cooldown is negative or not finite".into());
+            }
+        }
+        Ok(())
+    }
+
+    // The boss phase for an enemy of this kind with `health` left.
+    pub fn phase(&self, health: f32) -> Option<&BossPhase> {
+        let fraction = health / self.health;
+        self.boss
+            .as_ref()?
+            .phases
+            .iter()
+            .filter(|phase| fraction <= phase.below)
+            .min_by(|a, b| a.below.total_cmp(&b.below))
+    }
+}
+
+fn default_scale() -> f32 {
+    1.
+}
+
+fn default_tint() -> Srgba {
+    Srgba::WHITE
+}
+
+// The kinds of enemy in the game, in the order they are listed in the file.
+#[derive(Asset, TypePath, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnemyRegistry {
+    pub kinds: Vec<EnemyType>,
+}
+
+impl RonAsset for EnemyRegistry {
+    const EXTENSIONS: &'static [&'static str] = &["enemies.ron"];
+}
+
+// The built-in blob, used until the registry has loaded or if it fails to.
+impl Default for EnemyRegistry {
+    fn default() -> Self {
+        let sheet = |image: &str| SpriteSheet {
+            image: image.into(),
+            frame_size: UVec2::new(32, 32),
+            frames: 6,
+            first: 1,
+        };
+        Self {
+            kinds: vec![EnemyType {
+                name: "blob".into(),
+                sprite: sheet("blob.png"),
+                death: sheet("blob_death.png"),
+                health: 10.,
+                speed: 1.,
+                contact_damage: 1.2,
+                xp: 25,
+                scale: default_scale(),
+                weight: default_scale(),
+                tint: default_tint(),
+                spawn_weights: [Biome::Meadow, Biome::Wastes, Biome::Forest]
+                    .into_iter()
+                    .map(|biome| (biome, 1))
+                    .collect(),
+                ranged: None,
+                boss: None,
+            }],
+        }
+    }
+}
+
+impl EnemyRegistry {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.kinds.is_empty() {
+            return Err("it lists no kinds".into());
+        }
+        for enemy in &self.kinds {
+            enemy
+                .validate()
+                .map_err(|problem| format!("kind {:?}: {problem}", enemy.name))?;
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, kind: EnemyKind) -> &EnemyType {
+        self.kinds.get(kind.0).unwrap_or(&self.kinds[0])
+    }
+
+    // The scale of the largest kind, which bounds how far any of them reaches.
+    pub fn largest_scale(&self) -> f32 {
+        self.kinds
+            .iter()
+            .map(|enemy| enemy.scale)
+            .fold(1., f32::max)
+    }
+
+    pub fn find(&self, name: &str) -> Option<EnemyKind> {
+        self.kinds
+            .iter()
+            .position(|enemy| enemy.name == name)
+            .map(EnemyKind)
+    }
+
+    // Picks a kind for `biome` with `roll` in 0..1. Bosses are never picked.
+    pub fn pick(&self, biome: Biome, roll: f32) -> EnemyKind {
+        let table: Vec<(EnemyKind, u32)> = self
+            .kinds
+            .iter()
+            .enumerate()
+            .filter(|(_, enemy)| enemy.boss.is_none())
+            .filter_map(|(i, enemy)| {
+                let weight = *enemy.spawn_weights.get(&biome)?;
+                (weight > 0).then_some((EnemyKind(i), weight))
+            })
+            .collect();
+        if table.is_empty() {
+            return EnemyKind::default();
+        }
+        pick_weighted(&table, roll)
+    }
+}
+
+// Which entry of the registry an enemy is.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct EnemyKind(pub usize);
+
+// The registry in play, with an atlas layout for each kind's sheets.
+#[derive(Resource)]
+pub struct EnemyKinds {
+    pub handle: Handle<EnemyRegistry>,
+    pub registry: EnemyRegistry,
+    layouts: Vec<(Handle<TextureAtlasLayout>, Handle<TextureAtlasLayout>)>,
+}
+
+impl EnemyKinds {
+    pub fn new(
+        handle: Handle<EnemyRegistry>,
+        registry: EnemyRegistry,
+        atlases: &mut Assets<TextureAtlasLayout>,
+    ) -> Self {
+        let layouts = registry
+            .kinds
+            .iter()
+            .map(|enemy| {
+                (
+                    atlases.add(enemy.sprite.layout()),
+                    atlases.add(enemy.death.layout()),
+                )
+            })
+            .collect();
+        Self {
+            handle,
+            registry,
+            layouts,
+        }
+    }
+
+    pub fn get(&self, kind: EnemyKind) -> &EnemyType {
+        self.registry.get(kind)
+    }
+
+    pub fn sprite(&self, kind: EnemyKind, images: &Images) -> Sprite {
+        let (layout, _) = &self.layouts[self.index(kind)];
+        self.sheet_sprite(&self.get(kind).sprite, layout, images)
+    }
+
+    pub fn corpse(&self, kind: EnemyKind, images: &Images) -> Sprite {
+        let (_, layout) = &self.layouts[self.index(kind)];
+        self.sheet_sprite(&self.get(kind).death, layout, images)
+    }
+
+    fn index(&self, kind: EnemyKind) -> usize {
+        if kind.0 < self.layouts.len() {
+            kind.0
+        } else {
+            0
+        }
+    }
+
+    fn sheet_sprite(
+        &self,
+        sheet: &SpriteSheet,
+        layout: &Handle<TextureAtlasLayout>,
+        images: &Images,
+    ) -> Sprite {
+        Sprite {
+            image: images
+                .enemy_sheets
+                .get(&sheet.image)
+                .cloned()
+                .unwrap_or_default(),
+            texture_atlas: Some(TextureAtlas {
+                layout: layout.clone(),
+                index: sheet.indices().first,
+            }),
+            ..default()
+        }
+    }
+}
+
+pub fn load_enemy_kinds(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut atlases: ResMut<Assets<TextureAtlasLayout>>,
+    mut loading: ResMut<LoadingAssets>,
+) {
+    let handle = asset_server.load(ENEMY_REGISTRY);
+    loading.0.push(handle.clone().untyped());
+    commands.insert_resource(EnemyKinds::new(
+        handle,
+        EnemyRegistry::default(),
+        &mut atlases,
+    ));
+}
+
+// Live enemies keep their kind by name across a reload, so reordering the
+// file does not turn them into something else. Enemies of a kind that is no
+// longer listed become the first kind.
+pub fn sync_enemy_kinds(
+    mut events: EventReader<AssetEvent<EnemyRegistry>>,
+    registries: Res<Assets<EnemyRegistry>>,
+    mut atlases: ResMut<Assets<TextureAtlasLayout>>,
+    mut kinds: ResMut<EnemyKinds>,
+    mut enemy_query: Query<&mut EnemyKind>,
+) {
+    for event in events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) =
+            event
+        else {
+            continue;
+        };
+        if *id != kinds.handle.id() {
+            continue;
+        }
+        let Some(registry) = registries.get(*id) else {
+            continue;
+        };
+        if let Err(problem) = registry.validate() {
+            warn!("enemy registry is invalid, keeping the previous one: {problem}");
+            continue;
+        }
+        let remap: Vec<Option<EnemyKind>> = kinds
+            .registry
+            .kinds
+            .iter()
+            .map(|enemy| {
+                let kind = registry.find(&enemy.name);
+                if kind.is_none() {
+                    warn!("enemy kind {:?} was removed from the registry", enemy.name);
+                }
+                kind
+            })
+            .collect();
+        for mut kind in enemy_query.iter_mut() {
+            let new = remap.get(kind.0).copied().flatten().unwrap_or_default();
+            kind.set_if_neq(new);
+        }
+
+        let handle = kinds.handle.clone();
+        *kinds = EnemyKinds::new(handle, registry.clone(), &mut atlases);
+    }
+}
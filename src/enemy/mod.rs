@@ -1,21 +1,62 @@
+pub mod boss;
 pub mod components;
+pub mod elites;
+pub mod flow_field;
+pub mod kinds;
+pub mod steering;
 pub mod systems;
-use crate::{CollisionSet, DespawnSet, GameState, MovementSet, SpawnSet};
+pub mod waves;
+use crate::assets::RonAssetLoader;
+use crate::{
+    attacks::attack_collision, launch::god_mode, CollisionSet, DespawnSet, GameState,
+    MovementSet, NewRun, SpawnSet,
+};
 use bevy::prelude::*;
+use boss::*;
+use elites::*;
+use flow_field::*;
+use kinds::*;
+use steering::Steering;
 use systems::*;
+use waves::*;
 
 pub struct EnemyPlugin;
 
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(PostStartup, (setup_spawn_timer, setup_attack_timer))
+        app.init_asset::<EnemyRegistry>()
+            .init_resource::<Steering>()
+            .init_resource::<FlowField>()
+            .init_asset::<WaveSchedule>()
+            .init_asset_loader::<RonAssetLoader<EnemyRegistry>>()
+            .init_asset_loader::<RonAssetLoader<WaveSchedule>>()
+            .add_systems(PreStartup, (load_enemy_kinds, load_wave_schedule))
+            .add_systems(PreUpdate, (sync_enemy_kinds, sync_wave_schedule))
             .add_systems(
-                Update,
+                NewRun,
+                (setup_spawn_timer, setup_attack_timer, reset_flow_field),
+            )
+            .add_systems(
+                FixedUpdate,
                 (
                     spawn_enemies.in_set(SpawnSet),
+                    // Both draw spawn points from the same stream.
+                    spawn_bosses.in_set(SpawnSet).before(spawn_enemies),
+                    update_flow_field.in_set(MovementSet).before(enemy_movement),
                     enemy_movement.in_set(MovementSet),
-                    enemy_attack.in_set(CollisionSet),
-                    //color_change_cooldown,
+                    enemy_shoot.in_set(MovementSet).after(enemy_movement),
+                    boss_attack.in_set(MovementSet).after(enemy_movement),
+                    enemy_attack
+                        .in_set(CollisionSet)
+                        .after(attack_collision)
+                        .run_if(not(god_mode)),
+                    color_change_cooldown.after(CollisionSet),
+                    regenerate_elites.in_set(MovementSet),
+                    elite_deaths.in_set(DespawnSet).before(despawn_enemies),
+                    elite_explosions
+                        .in_set(DespawnSet)
+                        .before(despawn_enemies)
+                        .run_if(not(god_mode)),
                     despawn_enemies.in_set(DespawnSet),
                 )
                     .run_if(in_state(GameState::Running)),
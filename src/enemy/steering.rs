@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+
+// Local avoidance between enemies. Each one steers away from neighbours within
+// `radius`, more strongly the closer they are, and leans towards the way its
+// neighbours were heading so crowds flow around each other.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct Steering {
+    pub radius: f32,
+    pub separation: f32,
+    pub alignment: f32,
+}
+
+impl Default for Steering {
+    fn default() -> Self {
+        Self {
+            radius: 28.,
+            separation: 1.5,
+            alignment: 0.2,
+        }
+    }
+}
+
+// The direction an enemy moved in on the last tick, scaled by its speed
+// relative to its kind's top speed.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct Heading(pub Vec2);
+
+impl Steering {
+    // Combines the way to the player with the pull of the crowd within
+    // `radius`. The result is at most unit length, so crowding can slow an
+    // enemy down but never speed it up.
+    pub fn steer(
+        &self,
+        neighbours: impl Iterator<Item = (Entity, Vec2)>,
+        headings: impl Fn(Entity) -> Option<Vec2>,
+        entity: Entity,
+        position: Vec2,
+        seek: Vec2,
+    ) -> Vec2 {
+        let mut separation = Vec2::ZERO;
+        let mut alignment = Vec2::ZERO;
+        let mut count = 0;
+        for (other, other_position) in neighbours {
+            // Only other enemies count, not the props and pickups around.
+            let Some(heading) = headings(other).filter(|_| other != entity) else {
+                continue;
+            };
+            let offset = position - other_position;
+            let distance = offset.length();
+            // Enemies spawned on the same spot still need to split up, so pick
+            // a direction that only depends on who they are.
+            let away = offset
+                .try_normalize()
+                .unwrap_or_else(|| Vec2::from_angle(entity.index() as f32));
+            separation += away * (1. - distance / self.radius);
+            alignment += heading;
+            count += 1;
+        }
+        if count > 0 {
+            alignment /= count as f32;
+        }
+
+        (seek + separation * self.separation + alignment * self.alignment)
+            .clamp_length_max(1.)
+    }
+}
@@ -1,16 +1,34 @@
-use super::components::*;
+use super::{
+    components::*, elites::Elite, flow_field::FlowField, kinds::*, steering::*,
+    waves::Waves,
+};
+use crate::{
+    animation::*, assets::*, player::components::*, settings::Settings, BASE_MOVE_SPEED,
+};
 use crate::{
-    animation::*, assets::*, player::components::*, AUDIO_VOLUME, BASE_MOVE_SPEED,
+    bullet::{Bullet, Faction},
+    map::{biomes::pick_weighted, trees::Trees, ActiveMap, MapConfig, MapSeed},
+    pickups::pickup_bundle,
+    rng::{GameRng, RngStream},
+    spatial::SpatialGrid,
+    GlobalStopwatch, RunScoped,
 };
-use crate::{random_point_within_radius, GlobalStopwatch};
 
 use bevy::audio::{PlaybackMode, Volume};
-use bevy::prelude::*;
-use rand::rngs::SmallRng;
-use rand::{Rng, SeedableRng};
+use bevy::ecs::entity::EntityHashMap;
+use bevy::{color, prelude::*};
+use rand::Rng;
+use std::f32::consts::TAU;
 
-pub fn setup_spawn_timer(mut commands: Commands) {
-    commands.insert_resource(SpawnTimer::new());
+pub fn setup_spawn_timer(
+    mut commands: Commands,
+    mut rng: ResMut<GameRng>,
+    waves: Res<Waves>,
+) {
+    commands.insert_resource(SpawnTimer::new(
+        rng.stream(RngStream::EnemySpawn),
+        waves.schedule.waves.len(),
+    ));
 }
 
 pub fn setup_attack_timer(mut commands: Commands) {
@@ -21,136 +39,321 @@ pub fn spawn_enemies(
     mut commands: Commands,
     player_query: Query<&Transform, With<Player>>,
     icon: Res<Images>,
+    kinds: Res<EnemyKinds>,
+    waves: Res<Waves>,
     mut timer: ResMut<SpawnTimer>,
     time: Res<Time>,
-    mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
     watch: Res<GlobalStopwatch>,
+    mut game_rng: ResMut<GameRng>,
+    map: Res<ActiveMap>,
+    config: Res<MapConfig>,
+    seed: Res<MapSeed>,
+    boss_query: Query<(), With<Boss>>,
 ) {
     let Ok(&player_transform) = player_query.get_single() else {
         return;
     };
 
-    timer.countdown.tick(time.delta());
-
-    if timer.countdown.finished() {
-        let texture_handle = icon.blob.clone();
-        let texture_atlas =
-            TextureAtlasLayout::from_grid(UVec2::new(32, 32), 6, 1, None, None);
-        let texture_atlas_handle = texture_atlases.add(texture_atlas);
-
-        let mut rng = SmallRng::from_entropy();
-        let elapsed_time = watch.clock.elapsed_secs_f64();
-        let new_duration = (1. - elapsed_time / 120.).max(0.1);
-        let min_spawns = (elapsed_time / 60.).ceil() as i32;
-        let max_spawns = (elapsed_time / 30.).ceil() as i32;
-        let spawns: i32 = rng.gen_range(min_spawns..max_spawns.max(min_spawns + 1));
-        let x_start = player_transform.translation.x;
-        let y_start = player_transform.translation.y;
-
-        commands.spawn_batch((0..spawns).map(move |_| {
-            let (x_offset, y_offset) =
-                random_point_within_radius(&mut rng, x_start, y_start);
-            (
-                Sprite {
-                    image: texture_handle.clone(),
-                    texture_atlas: Some(TextureAtlas::from(texture_atlas_handle.clone())),
-                    ..default()
-                },
-                Transform::from_xyz(x_offset, y_offset, 1.),
-                Enemy {
-                    health: 10.,
-                    last_damage: 0.,
-                },
-                AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
-                AnimationIndices {
-                    first: 1,
-                    last: 5,
-                    current: 1,
-                },
-            )
-        }));
-
-        timer.countdown = Timer::from_seconds(new_duration as f32, TimerMode::Repeating);
+    let elapsed_time = watch.clock.elapsed_secs_f64();
+    let schedule = &waves.schedule.waves;
+    // A reloaded schedule with a different number of waves starts every wave
+    // over on its current interval.
+    if timer.countdowns.len() != schedule.len() {
+        timer.countdowns = schedule
+            .iter()
+            .map(|wave| {
+                Timer::from_seconds(wave.interval(elapsed_time), TimerMode::Repeating)
+            })
+            .collect();
+    }
+
+    let player = player_transform.translation.truncate();
+    for (wave, countdown) in schedule.iter().zip(timer.countdowns.iter_mut()) {
+        if !wave.active(elapsed_time) {
+            continue;
+        }
+        countdown.tick(time.delta());
+        if !countdown.finished() {
+            continue;
+        }
+
+        let rng = game_rng.stream(RngStream::EnemySpawn);
+        let mut spawns = wave.count(elapsed_time, rng);
+        // Waves thin out to a trickle while a boss is fighting.
+        if !boss_query.is_empty() {
+            spawns = spawns.min(1);
+        }
+
+        let table = wave.kind_table(&kinds.registry);
+        let enemies: Vec<(Vec2, EnemyKind)> = wave
+            .positions(spawns, &map.definition, rng, player)
+            .into_iter()
+            .map(|position| {
+                let kind = match &table {
+                    Some(table) => pick_weighted(table, rng.gen()),
+                    None => {
+                        let tile = config.tile_at(position);
+                        let biome = map.definition.biome_at(seed.0, tile);
+                        kinds.registry.pick(biome, rng.gen())
+                    }
+                };
+                (position, kind)
+            })
+            .collect();
+
+        let rng = game_rng.stream(RngStream::Elites);
+        for (position, kind) in enemies {
+            let elite = Elite::roll(elapsed_time, rng);
+            let health = kinds.get(kind).health;
+            let health = elite.as_ref().map_or(health, |elite| elite.health(health));
+            let mut enemy = commands.spawn(enemy_bundle(
+                &kinds,
+                &icon,
+                kind,
+                position.extend(1.),
+                health,
+            ));
+            if let Some(elite) = elite {
+                enemy.insert(elite);
+            }
+        }
+
+        *countdown =
+            Timer::from_seconds(wave.interval(elapsed_time), TimerMode::Repeating);
     }
 }
 
+pub fn enemy_bundle(
+    kinds: &EnemyKinds,
+    images: &Images,
+    kind: EnemyKind,
+    translation: Vec3,
+    health: f32,
+) -> impl Bundle {
+    let enemy_type = kinds.get(kind);
+    (
+        Sprite {
+            color: enemy_type.tint.into(),
+            ..kinds.sprite(kind, images)
+        },
+        Transform::from_translation(translation)
+            .with_scale(Vec3::splat(enemy_type.scale)),
+        Enemy {
+            health,
+            last_damage: 0.,
+        },
+        kind,
+        AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
+        enemy_type.sprite.indices(),
+    )
+}
+
 pub fn enemy_movement(
     mut enemy_query: Query<
-        (&mut Transform, &mut Enemy, &mut Sprite),
-        (With<Enemy>, Without<Player>),
+        (
+            Entity,
+            &mut Transform,
+            &Enemy,
+            &EnemyKind,
+            &mut Heading,
+            &mut Sprite,
+            &mut Knockback,
+            Option<&Elite>,
+        ),
+        Without<Player>,
     >,
     player_query: Query<&Transform, (With<Player>, Without<Enemy>)>,
+    kinds: Res<EnemyKinds>,
+    steering: Res<Steering>,
+    grid: Res<SpatialGrid>,
+    flow: Res<FlowField>,
     time: Res<Time>,
+    mut rng: ResMut<GameRng>,
+    trees: Trees,
 ) {
     let Ok(player_transform) = player_query.get_single() else {
         return;
     };
 
-    for (mut transform, enemy, mut sprite) in enemy_query.iter_mut() {
-        let diff = enemy.last_damage - time.elapsed_secs_f64();
-        if diff > -0.5 {
+    // Everyone steers around where the others stood at the end of the last
+    // tick, so the order enemies are moved in does not matter.
+    let headings: EntityHashMap<Vec2> = enemy_query
+        .iter()
+        .map(|(entity, _, _, _, heading, _, _, _)| (entity, heading.0))
+        .collect();
+
+    let rng = rng.stream(RngStream::EnemyMovement);
+
+    for (
+        entity,
+        mut transform,
+        enemy,
+        kind,
+        mut heading,
+        mut sprite,
+        mut knockback,
+        elite,
+    ) in enemy_query.iter_mut()
+    {
+        let enemy_type = kinds.get(*kind);
+        let phase_speed = enemy_type
+            .phase(enemy.health)
+            .map_or(1., |phase| phase.speed);
+        let elite_speed = elite.map_or(1., Elite::speed);
+        let speed = BASE_MOVE_SPEED * enemy_type.speed * phase_speed * elite_speed;
+
+        // Enemies drift with whatever knockback they have left, and only move
+        // on their own once it has mostly worn off.
+        if knockback.0 != Vec2::ZERO {
+            let position = trees.push_out(
+                transform.translation.truncate() + knockback.0 * time.delta_secs(),
+                ENEMY_RADIUS,
+            );
+            transform.translation = position.extend(transform.translation.z);
+            knockback.decay(time.delta_secs());
+        }
+        if knockback.staggered() {
+            heading.0 = Vec2::ZERO;
             continue;
         }
 
-        let mut rng = SmallRng::from_entropy();
         let chance = rng.gen_range(1..100);
 
         if chance <= 10 {
+            heading.0 = Vec2::ZERO;
             continue;
         } else if chance <= 25 {
             let x_offset = rng.gen_range(-1.0..1.0);
             let y_offset = rng.gen_range(-1.0..1.0);
-            transform.translation.x += x_offset * BASE_MOVE_SPEED * time.delta_secs();
-            transform.translation.y += y_offset * BASE_MOVE_SPEED * time.delta_secs();
+            transform.translation.x += x_offset * speed * time.delta_secs();
+            transform.translation.y += y_offset * speed * time.delta_secs();
         }
 
-        let direction = Vec2::new(
-            player_transform.translation.x - transform.translation.x,
-            player_transform.translation.y - transform.translation.y,
+        let position = transform.translation.truncate();
+        let to_player = player_transform.translation.truncate() - position;
+        let seek = match &enemy_type.ranged {
+            // Ranged kinds back off when the player gets close and hold still
+            // while it is in range.
+            Some(ranged) if to_player.length() < ranged.range * 0.75 => {
+                -to_player.normalize_or_zero()
+            }
+            Some(ranged) if to_player.length() < ranged.range => Vec2::ZERO,
+            _ => flow
+                .heading(position)
+                .unwrap_or_else(|| to_player.normalize_or_zero()),
+        };
+        heading.0 = steering.steer(
+            grid.within(position, steering.radius),
+            |other| headings.get(&other).copied(),
+            entity,
+            position,
+            seek,
         );
-        let direction = direction.normalize() * BASE_MOVE_SPEED;
 
-        transform.translation.x += direction.x * time.delta_secs();
-        transform.translation.y += direction.y * time.delta_secs();
+        let position = trees.push_out(
+            position + heading.0 * speed * time.delta_secs(),
+            ENEMY_RADIUS,
+        );
+        transform.translation = position.extend(transform.translation.z);
 
         sprite.flip_x = transform.translation.x > player_transform.translation.x;
     }
 }
 
+pub fn enemy_shoot(
+    mut commands: Commands,
+    mut enemy_query: Query<
+        (&Transform, &Enemy, &EnemyKind, &Knockback, &mut Reload),
+        Without<Player>,
+    >,
+    player_query: Query<&Transform, (With<Player>, Without<Enemy>)>,
+    kinds: Res<EnemyKinds>,
+    images: Res<Images>,
+    time: Res<Time>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+
+    let target = player_transform.translation.truncate();
+    for (transform, enemy, kind, knockback, mut reload) in enemy_query.iter_mut() {
+        let Some(ranged) = &kinds.get(*kind).ranged else {
+            continue;
+        };
+        reload.0.tick(time.delta());
+
+        let position = transform.translation.truncate();
+        if knockback.staggered()
+            || enemy.health <= 0.
+            || !reload.0.finished()
+            || position.distance(target) > ranged.range * 1.25
+        {
+            continue;
+        }
+
+        reload.0 = Timer::from_seconds(ranged.cooldown, TimerMode::Once);
+        commands.spawn((
+            Sprite::from_image(images.spit.clone()),
+            Transform::from_translation(position.extend(1.)),
+            Bullet::new(
+                (target - position).normalize_or_zero(),
+                ranged.projectile_speed,
+                ranged.damage,
+                Faction::Enemy,
+            ),
+        ));
+    }
+}
+
 pub fn enemy_attack(
     mut commands: Commands,
     audio: Res<Audio>,
+    settings: Res<Settings>,
     mut player_query: Query<(&mut Player, &Transform), (With<Player>, Without<Enemy>)>,
-    enemy_query: Query<(&Transform, &Enemy), (With<Enemy>, Without<Player>)>,
+    mut enemy_query: Query<
+        (&Transform, &mut Enemy, &EnemyKind, Option<&Elite>),
+        Without<Player>,
+    >,
+    kinds: Res<EnemyKinds>,
+    grid: Res<SpatialGrid>,
     mut attack_timer: ResMut<AttackTimer>,
     audio_query: Query<&PlayerHitSound>,
     time: Res<Time>,
+    mut rng: ResMut<GameRng>,
 ) {
     let Ok((mut player_struct, player_transform)) = player_query.get_single_mut() else {
         return;
     };
 
-    for (transform, enemy) in enemy_query.iter() {
+    // Every enemy touching the player lands a hit each time the timer comes
+    // round, however many others there are.
+    attack_timer.countdown.tick(time.delta());
+    let position = player_transform.translation.truncate();
+    let widest = reach_for_scale(CONTACT_REACH, kinds.registry.largest_scale());
+    for entity in grid.entities_within(position, widest) {
+        let Ok((transform, mut enemy, kind, elite)) = enemy_query.get_mut(entity) else {
+            continue;
+        };
         if enemy.health <= 0. {
             continue;
         }
+        let enemy_type = kinds.get(*kind);
 
         let distance = Vec2::new(
             player_transform.translation.x - transform.translation.x,
             player_transform.translation.y - transform.translation.y,
         );
 
-        attack_timer.countdown.tick(time.delta());
-
-        if distance.length() < 32. && attack_timer.countdown.finished() {
+        if distance.length() < reach_for_scale(CONTACT_REACH, enemy_type.scale)
+            && attack_timer.countdown.finished()
+        {
             if audio_query.is_empty() {
-                let mut rng = SmallRng::from_entropy();
                 commands.spawn((
                     AudioPlayer::<AudioSource>(audio.health_down.clone()),
                     PlaybackSettings {
                         mode: PlaybackMode::Once,
-                        volume: Volume::new(AUDIO_VOLUME / 2.),
-                        speed: rng.gen_range(0.95..1.05),
+                        volume: Volume::new(settings.sfx().get() / 2.),
+                        speed: rng.stream(RngStream::Audio).gen_range(0.95..1.05),
                         ..default()
                     },
                     PlayerHitSound {
@@ -158,69 +361,71 @@ pub fn enemy_attack(
                     },
                 ));
             }
-            player_struct.receive_damage();
+            player_struct.receive_damage(enemy_type.contact_damage);
             player_struct.last_damage = time.elapsed_secs_f64();
+            if let Some(elite) = elite {
+                elite.feed(&mut enemy, enemy_type.contact_damage, enemy_type.health);
+            }
         }
     }
 }
 
 pub fn despawn_enemies(
     mut commands: Commands,
-    enemy_query: Query<(Entity, &Enemy, &Transform), With<Enemy>>,
+    enemy_query: Query<(Entity, &Enemy, &EnemyKind, &Transform), With<Enemy>>,
     mut player_query: Query<&mut Player, With<Player>>,
     icon: Res<Images>,
-    mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
+    kinds: Res<EnemyKinds>,
 ) {
     let Ok(mut player) = player_query.get_single_mut() else {
         return;
     };
 
-    let corpses: Vec<Transform> = enemy_query
-        .iter()
-        .filter_map(|(entity, enemy, transform)| {
-            if enemy.health <= 0. {
-                commands.entity(entity).despawn();
-                player.gain_xp(25);
-                Some(*transform)
-            } else {
-                None
-            }
-        })
-        .collect();
-
-    let texture_handle = icon.blob_death.clone();
-    let texture_atlas =
-        TextureAtlasLayout::from_grid(UVec2::new(32, 32), 6, 1, None, None);
-    let texture_atlas_handle = texture_atlases.add(texture_atlas);
+    for (entity, enemy, kind, transform) in enemy_query.iter() {
+        if enemy.health > 0. {
+            continue;
+        }
 
-    commands.spawn_batch(corpses.into_iter().map(move |transform| {
-        (
+        let enemy_type = kinds.get(*kind);
+        commands.entity(entity).despawn();
+        player.gain_xp(enemy_type.xp);
+        let drops = enemy_type.boss.as_ref().map_or(0, |boss| boss.drops);
+        for i in 0..drops {
+            let offset = Vec2::from_angle(i as f32 / drops as f32 * TAU) * 40.;
+            commands.spawn(pickup_bundle(
+                icon.health_potion.clone(),
+                transform.translation.with_z(1.) + offset.extend(0.),
+            ));
+        }
+        commands.spawn((
             Sprite {
-                image: texture_handle.clone(),
-                texture_atlas: Some(TextureAtlas::from(texture_atlas_handle.clone())),
-                ..default()
+                color: enemy_type.tint.into(),
+                ..kinds.corpse(*kind, &icon)
             },
-            transform,
+            *transform,
+            RunScoped,
             AnimationTimerOnce(Timer::from_seconds(0.1, TimerMode::Repeating)),
-            AnimationIndices {
-                first: 1,
-                last: 5,
-                current: 1,
-            },
-        )
-    }));
+            enemy_type.death.indices(),
+        ));
+    }
 }
 
-// pub fn color_change_cooldown(
-//     mut enemy_query: Query<(&Enemy, &mut Sprite), With<Enemy>>,
-//     time: Res<Time>,
-// ) {
-//     for (enemy, mut sprite) in enemy_query.iter_mut() {
-//         let diff = enemy.last_damage - time.elapsed_secs_f64();
-//         if diff > -0.2 {
-//             sprite.color = Color::Srgba(color::palettes::basic::GRAY);
-//         } else if diff < -0.5 {
-//             sprite.color = Color::default();
-//         }
-//     }
-// }
+// Enemies flash grey when hit, then go back to the colour of their kind.
+pub fn color_change_cooldown(
+    mut enemy_query: Query<(&Enemy, &EnemyKind, Option<&Elite>, &mut Sprite)>,
+    kinds: Res<EnemyKinds>,
+    time: Res<Time>,
+) {
+    for (enemy, kind, elite, mut sprite) in enemy_query.iter_mut() {
+        let diff = enemy.last_damage - time.elapsed_secs_f64();
+        let color = if enemy.last_damage > 0. && diff > -0.2 {
+            Color::Srgba(color::palettes::basic::GRAY)
+        } else {
+            let tint = kinds.get(*kind).tint.into();
+            elite.map_or(tint, |elite| elite.tinted(tint))
+        };
+        if sprite.color != color {
+            sprite.color = color;
+        }
+    }
+}
@@ -0,0 +1,285 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use rand::{rngs::SmallRng, Rng};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    boss::BossEncounter,
+    kinds::{EnemyKind, EnemyRegistry},
+};
+use crate::{assets::RonAsset, map::definition::MapDefinition, LoadingAssets};
+
+pub const WAVE_SCHEDULE: &str = "waves/default.waves.ron";
+
+// However far a ramp runs, a spawn never brings more enemies than this or
+// waits longer than an hour.
+const MAX_COUNT: f64 = 1000.;
+const MAX_INTERVAL: f64 = 60. * 60.;
+
+// A value that changes steadily over a wave, from `start` when it opens, kept
+// between `min` and `max`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ramp {
+    pub start: f32,
+    #[serde(default)]
+    pub per_minute: f32,
+    #[serde(default)]
+    pub min: f32,
+    #[serde(default = "unbounded")]
+    pub max: f32,
+}
+
+fn unbounded() -> f32 {
+    f32::INFINITY
+}
+
+impl Ramp {
+    fn validate(&self, name: &str) -> Result<(), String> {
+        if !self.start.is_finite() || !self.per_minute.is_finite() {
+            return Err(format!(
+                "{name} does not start or change by a finite amount"
+            ));
+        }
+        if self.min.is_nan() || self.max.is_nan() || self.min > self.max {
+            return Err(format!("{name} has a min above its max"));
+        }
+        Ok(())
+    }
+
+    pub fn at(&self, seconds: f64) -> f64 {
+        (self.start as f64 + self.per_minute as f64 * seconds / 60.)
+            .clamp(self.min as f64, self.max as f64)
+    }
+}
+
+// How the enemies of one spawn are laid out.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Formation {
+    // Each enemy at its own point in the map's spawn zones.
+    #[default]
+    Scattered,
+    // Together around a single point in the spawn zones.
+    Cluster {
+        radius: f32,
+    },
+    // Evenly around the player.
+    Encircle {
+        radius: f32,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Wave {
+    // Seconds into the run the wave is active for. Ramps count from `from`.
+    pub from: f32,
+    #[serde(default)]
+    pub until: Option<f32>,
+    // Seconds between spawns.
+    pub interval: Ramp,
+    // Each spawn rolls a count from `min_count` up to but not including
+    // `max_count`, both rounded up, and always at least `min_count`.
+    pub min_count: Ramp,
+    pub max_count: Ramp,
+    // Weighted kinds to spawn by name, skipping any the registry lacks. Without
+    // any, kinds come from the spawn weights of the biome each enemy lands in.
+    #[serde(default)]
+    pub kinds: Vec<(String, u32)>,
+    #[serde(default)]
+    pub formation: Formation,
+}
+
+impl Wave {
+    // Intervals must stay above zero and counts at or above it, whatever the
+    // ramps do over the wave.
+    fn validate(&self) -> Result<(), String> {
+        self.interval.validate("interval")?;
+        self.min_count.validate("min_count")?;
+        self.max_count.validate("max_count")?;
+        if self.interval.min <= 0. {
+            return Err("interval can reach zero".into());
+        }
+        if self.min_count.min < 0. || self.max_count.min < 0. {
+            return Err("counts can go below zero".into());
+        }
+        if !self.from.is_finite() || self.until.is_some_and(|until| !until.is_finite()) {
+            return Err("from or until is not finite".into());
+        }
+        if let Formation::Cluster { radius } | Formation::Encircle { radius } =
+            self.formation
+        {
+            if !radius.is_finite() || radius < 0. {
+                return Err("the formation radius is negative or not finite".into());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn active(&self, elapsed: f64) -> bool {
+        elapsed >= self.from as f64
+            && self.until.is_none_or(|until| elapsed < until as f64)
+    }
+
+    fn since_start(&self, elapsed: f64) -> f64 {
+        elapsed - self.from as f64
+    }
+
+    pub fn interval(&self, elapsed: f64) -> f32 {
+        self.interval
+            .at(self.since_start(elapsed))
+            .min(MAX_INTERVAL) as f32
+    }
+
+    pub fn count(&self, elapsed: f64, rng: &mut SmallRng) -> i32 {
+        let since = self.since_start(elapsed);
+        let min = self.min_count.at(since).ceil().min(MAX_COUNT) as i32;
+        let max = self.max_count.at(since).ceil().min(MAX_COUNT) as i32;
+        rng.gen_range(min..max.max(min + 1))
+    }
+
+    // Where each of `count` enemies appears.
+    pub fn positions(
+        &self,
+        count: i32,
+        map: &MapDefinition,
+        rng: &mut SmallRng,
+        player: Vec2,
+    ) -> Vec<Vec2> {
+        match self.formation {
+            Formation::Scattered => {
+                (0..count).map(|_| map.spawn_point(rng, player)).collect()
+            }
+            Formation::Cluster { radius } => {
+                let centre = map.spawn_point(rng, player);
+                (0..count)
+                    .map(|_| {
+                        let offset = Vec2::from_angle(rng.gen_range(0.0..TAU))
+                            * rng.gen_range(0.0..=radius);
+                        map.clamp_to_bounds(centre + offset)
+                    })
+                    .collect()
+            }
+            Formation::Encircle { radius } => {
+                let start = rng.gen_range(0.0..TAU);
+                (0..count)
+                    .map(|i| {
+                        let angle = start + i as f32 / count as f32 * TAU;
+                        map.clamp_to_bounds(player + Vec2::from_angle(angle) * radius)
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    // The wave's own kinds that exist in `registry` and are not bosses, or
+    // `None` when it leaves the choice to the biome.
+    pub fn kind_table(&self, registry: &EnemyRegistry) -> Option<Vec<(EnemyKind, u32)>> {
+        let table: Vec<(EnemyKind, u32)> = self
+            .kinds
+            .iter()
+            .filter(|(_, weight)| *weight > 0)
+            .filter_map(|(name, weight)| Some((registry.find(name)?, *weight)))
+            .filter(|(kind, _)| registry.get(*kind).boss.is_none())
+            .collect();
+        (!table.is_empty()).then_some(table)
+    }
+}
+
+// The run's spawn pacing, as a list of waves that may overlap, and the bosses
+// that interrupt them.
+#[derive(Asset, TypePath, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WaveSchedule {
+    pub waves: Vec<Wave>,
+    #[serde(default)]
+    pub bosses: Vec<BossEncounter>,
+}
+
+impl WaveSchedule {
+    pub fn validate(&self) -> Result<(), String> {
+        for (i, wave) in self.waves.iter().enumerate() {
+            wave.validate()
+                .map_err(|problem| format!("wave {i}: {problem}"))?;
+        }
+        Ok(())
+    }
+}
+
+impl RonAsset for WaveSchedule {
+    const EXTENSIONS: &'static [&'static str] = &["waves.ron"];
+}
+
+// One endless wave that speeds up over the first two minutes and spawns more
+// enemies at once every half minute, with a blob king at five and ten minutes.
+// Used until the schedule has loaded or if it fails to.
+impl Default for WaveSchedule {
+    fn default() -> Self {
+        let ramp = |start, per_minute, min| Ramp {
+            start,
+            per_minute,
+            min,
+            max: unbounded(),
+        };
+        Self {
+            waves: vec![Wave {
+                from: 0.,
+                until: None,
+                interval: ramp(1., -0.5, 0.1),
+                min_count: ramp(0., 1., 0.),
+                max_count: ramp(0., 2., 0.),
+                kinds: Vec::new(),
+                formation: Formation::Scattered,
+            }],
+            bosses: [300., 600.]
+                .into_iter()
+                .map(|at| BossEncounter {
+                    at,
+                    kind: "blob king".into(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct Waves {
+    pub handle: Handle<WaveSchedule>,
+    pub schedule: WaveSchedule,
+}
+
+pub fn load_wave_schedule(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut loading: ResMut<LoadingAssets>,
+) {
+    let handle = asset_server.load(WAVE_SCHEDULE);
+    loading.0.push(handle.clone().untyped());
+    commands.insert_resource(Waves {
+        handle,
+        schedule: WaveSchedule::default(),
+    });
+}
+
+pub fn sync_wave_schedule(
+    mut events: EventReader<AssetEvent<WaveSchedule>>,
+    schedules: Res<Assets<WaveSchedule>>,
+    mut waves: ResMut<Waves>,
+) {
+    for event in events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) =
+            event
+        else {
+            continue;
+        };
+        if *id != waves.handle.id() {
+            continue;
+        }
+        let Some(schedule) = schedules.get(*id) else {
+            continue;
+        };
+        if let Err(problem) = schedule.validate() {
+            warn!("wave schedule is invalid, keeping the previous one: {problem}");
+            continue;
+        }
+        waves.schedule = schedule.clone();
+    }
+}
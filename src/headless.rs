@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use bevy::{
+    app::ScheduleRunnerPlugin, log::LogPlugin, prelude::*, state::app::StatesPlugin,
+    time::TimeUpdateStrategy,
+};
+
+use crate::{
+    assets::{Audio, Images},
+    player::{
+        components::Player,
+        levelup::{apply_level_up_choice, LevelUpChoice, MenuButtonAction},
+    },
+    replay::ReplayPlayback,
+    GameState, GlobalStopwatch,
+};
+
+#[derive(Resource)]
+pub struct TickLimit(pub u32);
+
+// Fixed ticks since loading finished, so the limit does not depend on how many
+// frames the asset server took.
+#[derive(Resource, Default)]
+pub struct TickCount(pub u32);
+
+pub struct HeadlessPlugin;
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(Duration::ZERO)),
+            StatesPlugin,
+            bevy::input::InputPlugin,
+            // Runs play out the same way even if asset files change meanwhile.
+            AssetPlugin {
+                watch_for_changes_override: Some(false),
+                ..default()
+            },
+        ))
+        .init_asset::<TextureAtlasLayout>()
+        .add_systems(PreStartup, (setup_stub_assets, step_one_tick_per_frame));
+    }
+}
+
+pub struct HeadlessRunPlugin {
+    pub ticks: u32,
+}
+
+impl Plugin for HeadlessRunPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(LogPlugin::default())
+            .insert_resource(TickLimit(self.ticks))
+            .init_resource::<TickCount>()
+            .add_systems(
+                FixedFirst,
+                count_tick.run_if(not(in_state(GameState::Loading))),
+            )
+            .add_systems(
+                Update,
+                choose_level_up
+                    .before(apply_level_up_choice)
+                    .run_if(in_state(GameState::LevelUpScreen))
+                    .run_if(not(resource_exists::<ReplayPlayback>)),
+            )
+            .add_systems(PostUpdate, exit_after_ticks);
+    }
+}
+
+fn setup_stub_assets(mut commands: Commands) {
+    commands.insert_resource(Images::default());
+    commands.insert_resource(Audio::default());
+}
+
+fn choose_level_up(
+    player_query: Query<&Player>,
+    mut choices: EventWriter<LevelUpChoice>,
+) {
+    let Ok(player) = player_query.get_single() else {
+        return;
+    };
+
+    let choice = match player.level % 3 {
+        0 => MenuButtonAction::AttackSpeed,
+        1 => MenuButtonAction::MovementSpeed,
+        _ => MenuButtonAction::Health,
+    };
+    choices.send(LevelUpChoice(choice));
+}
+
+// Each headless frame advances time by exactly one fixed timestep, so `--ticks`
+// counts simulation ticks regardless of the configured tick rate.
+fn step_one_tick_per_frame(mut commands: Commands, fixed_time: Res<Time<Fixed>>) {
+    commands.insert_resource(TimeUpdateStrategy::ManualDuration(fixed_time.timestep()));
+}
+
+fn count_tick(mut ticks: ResMut<TickCount>) {
+    ticks.0 += 1;
+}
+
+fn exit_after_ticks(
+    ticks: Res<TickCount>,
+    limit: Res<TickLimit>,
+    state: Res<State<GameState>>,
+    player_query: Query<&Player>,
+    stopwatch: Option<Res<GlobalStopwatch>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    let game_over = *state == GameState::GameOver;
+    if ticks.0 < limit.0 && !game_over {
+        return;
+    }
+
+    let elapsed = stopwatch.map_or(0., |watch| watch.clock.elapsed_secs());
+    match player_query.get_single() {
+        Ok(player) => info!(
+            "headless run ended after {} ticks ({:.1}s survived): level {}, xp {}, health {:.1}/{}",
+            ticks.0, elapsed, player.level, player.xp, player.health, player.max_health
+        ),
+        Err(_) => info!(
+            "headless run ended after {} ticks ({:.1}s survived): player died",
+            ticks.0, elapsed
+        ),
+    }
+    exit.send(AppExit::Success);
+}
@@ -0,0 +1,65 @@
+use bevy::{
+    app::RunFixedMainLoopSystem,
+    ecs::{component::ComponentId, world::DeferredWorld},
+    prelude::*,
+};
+
+// Gameplay moves `Transform` on the fixed timestep. Between ticks the rendered
+// transform is blended from the previous tick towards the current one, and put
+// back to the simulated value before the next batch of ticks runs.
+#[derive(Component, Default)]
+#[component(on_add = snap_to_transform)]
+pub struct Interpolated {
+    pub previous: Vec3,
+    pub current: Vec3,
+}
+
+fn snap_to_transform(mut world: DeferredWorld, entity: Entity, _: ComponentId) {
+    let Some(translation) = world.get::<Transform>(entity).map(|t| t.translation) else {
+        return;
+    };
+    if let Some(mut interpolated) = world.get_mut::<Interpolated>(entity) {
+        interpolated.previous = translation;
+        interpolated.current = translation;
+    }
+}
+
+pub struct InterpolationPlugin;
+
+impl Plugin for InterpolationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            RunFixedMainLoop,
+            (
+                restore_simulated_translation
+                    .in_set(RunFixedMainLoopSystem::BeforeFixedMainLoop),
+                interpolate_translation
+                    .in_set(RunFixedMainLoopSystem::AfterFixedMainLoop),
+            ),
+        )
+        .add_systems(FixedFirst, store_previous_translation);
+    }
+}
+
+fn restore_simulated_translation(mut query: Query<(&mut Transform, &Interpolated)>) {
+    for (mut transform, interpolated) in query.iter_mut() {
+        transform.translation = interpolated.current;
+    }
+}
+
+fn store_previous_translation(mut query: Query<(&Transform, &mut Interpolated)>) {
+    for (transform, mut interpolated) in query.iter_mut() {
+        interpolated.previous = transform.translation;
+    }
+}
+
+fn interpolate_translation(
+    mut query: Query<(&mut Transform, &mut Interpolated)>,
+    time: Res<Time<Fixed>>,
+) {
+    let alpha = time.overstep_fraction();
+    for (mut transform, mut interpolated) in query.iter_mut() {
+        interpolated.current = transform.translation;
+        transform.translation = interpolated.previous.lerp(interpolated.current, alpha);
+    }
+}
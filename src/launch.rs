@@ -0,0 +1,114 @@
+use std::{path::PathBuf, str::FromStr};
+
+use bevy::prelude::*;
+
+// The highest level whose XP threshold still fits in a `u32`.
+const MAX_START_LEVEL: u32 = 23;
+
+pub const USAGE: &str = "\
+usage: bevy_hell [options]
+
+  --seed <n>           seed the game RNG
+  --headless           run without a window, audio or UI
+  --ticks <n>          stop a headless run after n fixed ticks
+  --tick-rate <hz>     fixed gameplay tick rate
+  --start-level <n>    start every run at this player level, up to 23
+  --god                take no damage and enable the debug XP key
+  --no-audio           mute all music and sound effects
+  --window <w>x<h>     window size for this session
+  --record <file>      record input to a replay file
+  --replay <file>      play back a replay file
+  --debug-overlay      show position, memory, CPU and enemy count
+  --map <file>         map to play, relative to the assets folder
+  -h, --help           print this message";
+
+// Parsed from the command line before the app is built. `main` inserts it ahead
+// of the plugins so their `build` can already read it.
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+pub struct LaunchOptions {
+    pub help: bool,
+    pub seed: Option<u64>,
+    pub headless: bool,
+    pub ticks: Option<u32>,
+    pub tick_rate: Option<f64>,
+    pub start_level: Option<u32>,
+    pub god: bool,
+    pub no_audio: bool,
+    pub window: Option<(f32, f32)>,
+    pub record: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+    pub debug_overlay: bool,
+    pub map: Option<PathBuf>,
+}
+
+impl LaunchOptions {
+    pub fn parse<I>(args: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => options.help = true,
+                "--seed" => options.seed = Some(value(&arg, args.next())?),
+                "--headless" => options.headless = true,
+                "--ticks" => options.ticks = Some(value(&arg, args.next())?),
+                "--tick-rate" => {
+                    let hz: f64 = value(&arg, args.next())?;
+                    if !hz.is_finite() || hz <= 0. {
+                        return Err(format!("{arg} must be a positive number"));
+                    }
+                    options.tick_rate = Some(hz);
+                }
+                "--start-level" => {
+                    let level: u32 = value(&arg, args.next())?;
+                    if level == 0 || level > MAX_START_LEVEL {
+                        return Err(format!("{arg} must be from 1 to {MAX_START_LEVEL}"));
+                    }
+                    options.start_level = Some(level);
+                }
+                "--god" => options.god = true,
+                "--no-audio" => options.no_audio = true,
+                "--window" => {
+                    let size: String = value(&arg, args.next())?;
+                    options.window = Some(parse_size(&size).ok_or_else(|| {
+                        format!("{arg} expects <width>x<height>, got '{size}'")
+                    })?);
+                }
+                "--record" => options.record = Some(value(&arg, args.next())?),
+                "--replay" => options.replay = Some(value(&arg, args.next())?),
+                "--debug-overlay" => options.debug_overlay = true,
+                "--map" => options.map = Some(value(&arg, args.next())?),
+                _ => return Err(format!("unknown argument '{arg}'")),
+            }
+        }
+        Ok(options)
+    }
+}
+
+fn value<T: FromStr>(name: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("{name} expects a value"))?;
+    value
+        .parse()
+        .map_err(|_| format!("{name} got an invalid value '{value}'"))
+}
+
+fn parse_size(size: &str) -> Option<(f32, f32)> {
+    let (width, height) = size.split_once('x')?;
+    let width: f32 = width.parse().ok()?;
+    let height: f32 = height.parse().ok()?;
+    (width > 0. && height > 0.).then_some((width, height))
+}
+
+pub fn god_mode(options: Res<LaunchOptions>) -> bool {
+    options.god
+}
+
+pub fn no_audio(options: Res<LaunchOptions>) -> bool {
+    options.no_audio
+}
+
+pub fn debug_overlay(options: Res<LaunchOptions>) -> bool {
+    options.debug_overlay
+}
@@ -0,0 +1,292 @@
+pub mod animation;
+pub mod assets;
+pub mod attacks;
+pub mod bullet;
+pub mod camera;
+pub mod debug;
+pub mod enemy;
+pub mod headless;
+pub mod input;
+pub mod interpolation;
+pub mod launch;
+pub mod map;
+pub mod pickups;
+pub mod player;
+pub mod props;
+pub mod replay;
+pub mod rng;
+pub mod save;
+pub mod settings;
+pub mod spatial;
+pub mod ui;
+
+use bevy::{
+    app::PluginGroupBuilder, asset::LoadState, ecs::schedule::ScheduleLabel, prelude::*,
+    time::Stopwatch,
+};
+use launch::LaunchOptions;
+use settings::{Action, Settings};
+
+pub use attacks::Attack;
+pub use enemy::components::Enemy;
+pub use player::components::Player;
+
+pub const SCREEN_WIDTH: f32 = 1280.;
+pub const SCREEN_HEIGHT: f32 = 720.;
+
+pub const BASE_MOVE_SPEED: f32 = 100.;
+
+pub const DEFAULT_TICK_RATE: f64 = 60.;
+
+#[derive(
+    SystemSet, States, Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Reflect,
+)]
+pub enum GameState {
+    #[default]
+    Loading,
+    Running,
+    GameOver,
+    Paused,
+    LevelUpScreen,
+}
+
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
+pub struct InputSet;
+
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
+pub struct MovementSet;
+
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
+pub struct CollisionSet;
+
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
+pub struct DespawnSet;
+
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
+pub struct SpawnSet;
+
+#[derive(Resource, Default)]
+pub struct GlobalStopwatch {
+    pub clock: Stopwatch,
+}
+
+// Gameplay entities that belong to a single run and are despawned when the
+// next one starts.
+#[derive(Component, Default)]
+pub struct RunScoped;
+
+// Runs whenever a fresh run starts, after run-scoped entities are despawned and
+// the clock and RNG are reset. Plugins reinitialise their run resources here.
+#[derive(ScheduleLabel, Debug, Hash, PartialEq, Eq, Clone)]
+pub struct NewRun;
+
+// Assets the first run depends on. Plugins add their handles during startup,
+// and the game stays in `GameState::Loading` until every one has loaded or
+// failed.
+#[derive(Resource, Default)]
+pub struct LoadingAssets(pub Vec<UntypedHandle>);
+
+pub struct BevyHellPlugins;
+
+impl PluginGroup for BevyHellPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add(GamePlugin)
+            .add(settings::SettingsPlugin::default())
+            .add(interpolation::InterpolationPlugin)
+            .add(assets::AssetLoader)
+            .add(camera::CameraPlugin)
+            .add(player::PlayerPlugin)
+            .add(input::InputPlugin)
+            .add(enemy::EnemyPlugin)
+            .add(spatial::SpatialPlugin)
+            .add(bullet::BulletPlugin)
+            .add(map::MapPlugin)
+            .add(attacks::AttackPlugin)
+            .add(animation::AnimationPlugin)
+            .add(ui::UIPlugin)
+            .add(pickups::PickupPlugin)
+            .add(props::PropPlugin)
+            .add(save::SavePlugin::default())
+            .add(debug::DebugPlugin)
+    }
+}
+
+impl BevyHellPlugins {
+    pub fn headless() -> PluginGroupBuilder {
+        Self.build()
+            .disable::<assets::AssetLoader>()
+            .disable::<camera::CameraPlugin>()
+            .disable::<ui::UIPlugin>()
+            .disable::<save::SavePlugin>()
+            .set(settings::SettingsPlugin { path: None })
+            .disable::<debug::DebugPlugin>()
+    }
+}
+
+pub struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        let options = app
+            .world()
+            .get_resource::<LaunchOptions>()
+            .cloned()
+            .unwrap_or_default();
+
+        app.insert_resource(Time::<Fixed>::from_hz(
+            options.tick_rate.unwrap_or(DEFAULT_TICK_RATE),
+        ))
+        .insert_resource(
+            options
+                .seed
+                .map_or_else(rng::GameRng::default, rng::GameRng::new),
+        )
+        .insert_resource(options)
+        .configure_sets(
+            FixedUpdate,
+            (
+                InputSet.before(SpawnSet),
+                SpawnSet.before(MovementSet),
+                MovementSet.before(CollisionSet),
+                CollisionSet.before(DespawnSet),
+                DespawnSet.after(CollisionSet),
+            ),
+        )
+        .init_state::<GameState>()
+        .enable_state_scoped_entities::<GameState>()
+        .init_resource::<GlobalStopwatch>()
+        .init_resource::<LoadingAssets>()
+        .init_schedule(NewRun)
+        .add_systems(Startup, rng::log_seed)
+        .add_systems(
+            Update,
+            (
+                finish_loading.run_if(in_state(GameState::Loading)),
+                listen_for_restart.run_if(in_state(GameState::GameOver)),
+                listen_for_game_pause.run_if(in_state(GameState::Running)),
+                listen_for_unpause.run_if(in_state(GameState::Paused)),
+            ),
+        )
+        .add_systems(
+            FixedUpdate,
+            tick_clock
+                .before(SpawnSet)
+                .run_if(in_state(GameState::Running)),
+        )
+        .add_systems(
+            OnTransition {
+                exited: GameState::Loading,
+                entered: GameState::Running,
+            },
+            start_new_run,
+        )
+        .add_systems(
+            OnTransition {
+                exited: GameState::GameOver,
+                entered: GameState::Running,
+            },
+            start_new_run,
+        )
+        .add_systems(OnEnter(GameState::Running), unpause_clock)
+        .add_systems(OnExit(GameState::Running), pause_clock);
+    }
+}
+
+pub fn finish_loading(
+    asset_server: Res<AssetServer>,
+    mut loading: ResMut<LoadingAssets>,
+    mut fixed_time: ResMut<Time<Fixed>>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    loading
+        .0
+        .retain(|handle| match asset_server.load_state(handle.id()) {
+            LoadState::Loaded => false,
+            LoadState::Failed(err) => {
+                warn!("{err}, using the built-in default");
+                false
+            }
+            _ => true,
+        });
+    if !loading.0.is_empty() {
+        return;
+    }
+
+    // However long loading took, the first run starts from a clean fixed
+    // timestep so it plays out the same way every time.
+    let overstep = fixed_time.overstep();
+    fixed_time.discard_overstep(overstep);
+    game_state.set(GameState::Running);
+}
+
+pub fn assets_loaded(loading: Res<LoadingAssets>) -> bool {
+    loading.0.is_empty()
+}
+
+pub fn start_new_run(world: &mut World) {
+    let entities: Vec<Entity> = world
+        .query_filtered::<Entity, With<RunScoped>>()
+        .iter(world)
+        .collect();
+    for entity in entities {
+        if let Ok(entity) = world.get_entity_mut(entity) {
+            entity.despawn_recursive();
+        }
+    }
+
+    world.resource_mut::<GlobalStopwatch>().clock.reset();
+    world.resource_mut::<rng::GameRng>().reset();
+    world.run_schedule(NewRun);
+}
+
+fn tick_clock(mut stopwatch: ResMut<GlobalStopwatch>, time: Res<Time>) {
+    stopwatch.clock.tick(time.delta());
+}
+
+fn pause_clock(mut stopwatch: ResMut<GlobalStopwatch>) {
+    stopwatch.clock.pause();
+}
+
+fn unpause_clock(mut stopwatch: ResMut<GlobalStopwatch>) {
+    stopwatch.clock.unpause();
+}
+
+fn listen_for_restart(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    if settings
+        .key_bindings
+        .just_pressed(&keyboard_input, Action::Restart)
+    {
+        game_state.set(GameState::Running);
+    }
+}
+
+fn listen_for_game_pause(
+    mut game_state: ResMut<NextState<GameState>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+) {
+    if settings
+        .key_bindings
+        .just_pressed(&keyboard_input, Action::Pause)
+    {
+        game_state.set(GameState::Paused);
+    }
+}
+
+fn listen_for_unpause(
+    mut game_state: ResMut<NextState<GameState>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+) {
+    if settings
+        .key_bindings
+        .just_pressed(&keyboard_input, Action::Pause)
+    {
+        game_state.set(GameState::Running);
+    }
+}
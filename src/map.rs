@@ -1,7 +0,0 @@
-use bevy::prelude::*;
-
-pub struct MapPlugin;
-
-impl Plugin for MapPlugin {
-    fn build(&self, _app: &mut App) {}
-}
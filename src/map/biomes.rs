@@ -0,0 +1,76 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{chunks::Ground, noise::value_noise};
+
+const BIOME_SALT: u64 = 0xB10E;
+// In tiles, so a biome spans a couple of screens.
+const BIOME_SCALE: f32 = 64.;
+const WASTES_BELOW: f32 = 0.38;
+const FOREST_ABOVE: f32 = 0.62;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Biome {
+    Meadow,
+    Wastes,
+    Forest,
+}
+
+pub struct BiomeDef {
+    // Weights over the patch noise, so each ground type still forms patches.
+    pub ground: &'static [(Ground, u32)],
+    pub tint: Color,
+    pub tree_density: f32,
+}
+
+const MEADOW: BiomeDef = BiomeDef {
+    ground: &[(Ground::Grass, 3), (Ground::Dirt, 2)],
+    tint: Color::WHITE,
+    tree_density: 0.03,
+};
+
+const WASTES: BiomeDef = BiomeDef {
+    ground: &[(Ground::Grass, 1), (Ground::Dirt, 4)],
+    tint: Color::srgb(1., 0.92, 0.78),
+    tree_density: 0.005,
+};
+
+const FOREST: BiomeDef = BiomeDef {
+    ground: &[(Ground::Grass, 4), (Ground::Dirt, 1)],
+    tint: Color::srgb(0.72, 0.88, 0.72),
+    tree_density: 0.1,
+};
+
+impl Biome {
+    pub fn def(self) -> &'static BiomeDef {
+        match self {
+            Biome::Meadow => &MEADOW,
+            Biome::Wastes => &WASTES,
+            Biome::Forest => &FOREST,
+        }
+    }
+}
+
+pub fn biome_at(seed: u64, tile: IVec2) -> Biome {
+    let value = value_noise(seed ^ BIOME_SALT, tile.as_vec2() / BIOME_SCALE);
+    if value < WASTES_BELOW {
+        Biome::Wastes
+    } else if value > FOREST_ABOVE {
+        Biome::Forest
+    } else {
+        Biome::Meadow
+    }
+}
+
+// Picks from a weighted table with `roll` in 0..1, walking the entries in order.
+pub fn pick_weighted<T: Copy>(table: &[(T, u32)], roll: f32) -> T {
+    let total: u32 = table.iter().map(|(_, weight)| weight).sum();
+    let mut target = roll * total as f32;
+    for (item, weight) in table {
+        if target < *weight as f32 {
+            return *item;
+        }
+        target -= *weight as f32;
+    }
+    table[table.len() - 1].0
+}
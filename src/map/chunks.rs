@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    definition::MapDefinition, noise::value_noise, ActiveMap, MapConfig, MapSeed,
+};
+use crate::{assets::Images, player::components::Player};
+
+const GROUND_Z: f32 = -10.;
+// Relative to the chunk, so trees end up drawn above the actors.
+const TREE_Z: f32 = 12.;
+const DIRT_SCALE: f32 = 8.;
+const DIRT_THRESHOLD: f32 = 0.6;
+
+#[derive(Component)]
+pub struct Chunk(pub IVec2);
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Ground {
+    Grass,
+    Dirt,
+}
+
+#[derive(Component)]
+pub struct Tree;
+
+#[derive(Resource, Default)]
+pub struct LoadedChunks(pub HashMap<IVec2, Entity>);
+
+pub fn ground_at(seed: u64, tile: IVec2) -> Ground {
+    if patch_noise(seed, tile) > DIRT_THRESHOLD {
+        Ground::Dirt
+    } else {
+        Ground::Grass
+    }
+}
+
+pub fn patch_noise(seed: u64, tile: IVec2) -> f32 {
+    value_noise(seed, tile.as_vec2() / DIRT_SCALE)
+}
+
+pub fn update_chunks(
+    mut commands: Commands,
+    config: Res<MapConfig>,
+    mut loaded: ResMut<LoadedChunks>,
+    player_query: Query<&Transform, With<Player>>,
+    icons: Res<Images>,
+    seed: Res<MapSeed>,
+    map: Res<ActiveMap>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+
+    // A new layout, seed or map invalidates every chunk already spawned.
+    if (config.is_changed() && !config.is_added())
+        || (seed.is_changed() && !seed.is_added())
+        || (map.is_changed() && !map.is_added())
+    {
+        for (_, entity) in loaded.0.drain() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+
+    let centre = config.chunk_at(player_transform.translation.truncate());
+    let view_distance = config.view_distance;
+    loaded.0.retain(|coord, entity| {
+        let keep = (*coord - centre).abs().max_element() <= view_distance;
+        if !keep {
+            commands.entity(*entity).despawn_recursive();
+        }
+        keep
+    });
+
+    for y in -view_distance..=view_distance {
+        for x in -view_distance..=view_distance {
+            let coord = centre + IVec2::new(x, y);
+            if loaded.0.contains_key(&coord) {
+                continue;
+            }
+            let entity = spawn_chunk(
+                &mut commands,
+                &config,
+                &map.definition,
+                &icons,
+                seed.0,
+                coord,
+            );
+            loaded.0.insert(coord, entity);
+        }
+    }
+}
+
+fn spawn_chunk(
+    commands: &mut Commands,
+    config: &MapConfig,
+    map: &MapDefinition,
+    icons: &Images,
+    seed: u64,
+    coord: IVec2,
+) -> Entity {
+    let size = config.chunk_size as i32;
+    let origin = coord.as_vec2() * config.chunk_world_size();
+
+    commands
+        .spawn((
+            Chunk(coord),
+            Transform::from_translation(origin.extend(GROUND_Z)),
+            Visibility::default(),
+        ))
+        .with_children(|parent| {
+            for y in 0..size {
+                for x in 0..size {
+                    let tile = coord * size + IVec2::new(x, y);
+                    let offset = (Vec2::new(x as f32, y as f32) + 0.5) * config.tile_size;
+                    if !map.in_bounds(origin + offset) {
+                        continue;
+                    }
+                    let ground = map.ground_at(seed, tile);
+                    let image = match ground {
+                        Ground::Grass => icons.grass.clone(),
+                        Ground::Dirt => icons.dirt.clone(),
+                    };
+                    parent.spawn((
+                        Sprite {
+                            image,
+                            color: map.biome_at(seed, tile).def().tint,
+                            ..default()
+                        },
+                        Transform::from_translation(offset.extend(0.)),
+                        ground,
+                    ));
+                    if map.tree_at(seed, tile) {
+                        parent.spawn((
+                            Sprite::from_image(icons.tree.clone()),
+                            Transform::from_translation(offset.extend(TREE_Z)),
+                            Tree,
+                        ));
+                    }
+                }
+            }
+        })
+        .id()
+}
@@ -0,0 +1,223 @@
+use std::{
+    collections::{HashMap, HashSet},
+    f32::consts::PI,
+};
+
+use bevy::prelude::*;
+use rand::{rngs::SmallRng, Rng};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    biomes::{self, pick_weighted, Biome},
+    chunks::{self, Ground},
+    trees,
+};
+use crate::{
+    assets::RonAsset,
+    props::{self, PropKind},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GroundLayer {
+    // Meadow everywhere, as before biomes.
+    Noise,
+    Biomes,
+    Uniform(Ground),
+}
+
+// `Ring` is measured from the player, `Area` is a fixed rectangle of the world.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SpawnZone {
+    Ring { inner: f32, outer: f32 },
+    Area(Rect),
+}
+
+// Whether `rect` is finite and has some width and height.
+fn is_solid(rect: &Rect) -> bool {
+    rect.min.is_finite() && rect.max.is_finite() && rect.min.cmplt(rect.max).all()
+}
+
+impl SpawnZone {
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            SpawnZone::Ring { inner, outer } => {
+                if !inner.is_finite() || !outer.is_finite() || *inner < 0. {
+                    return Err("a ring has a negative or non-finite radius".into());
+                }
+                if inner >= outer {
+                    return Err("a ring's inner radius is not below its outer one".into());
+                }
+            }
+            SpawnZone::Area(area) => {
+                if !is_solid(area) {
+                    return Err("an area is empty, inverted or not finite".into());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn sample(&self, rng: &mut SmallRng, player: Vec2) -> Vec2 {
+        match self {
+            SpawnZone::Ring { inner, outer } => {
+                let angle = rng.gen_range(0.0..PI * 2.0);
+                let distance = rng.gen_range(*inner..*outer);
+                player + Vec2::from_angle(angle) * distance
+            }
+            SpawnZone::Area(area) => Vec2::new(
+                rng.gen_range(area.min.x..area.max.x),
+                rng.gen_range(area.min.y..area.max.y),
+            ),
+        }
+    }
+}
+
+#[derive(Asset, TypePath, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MapDefinition {
+    pub ground: GroundLayer,
+    pub ground_tiles: HashMap<IVec2, Ground>,
+    pub scatter_trees: bool,
+    pub trees: HashSet<IVec2>,
+    pub scatter_props: bool,
+    pub props: HashMap<IVec2, PropKind>,
+    pub spawn_zones: Vec<SpawnZone>,
+    pub pickup_points: Vec<Vec2>,
+    pub bounds: Option<Rect>,
+}
+
+// The endless procedural map, used until a map file is loaded or if it fails.
+impl Default for MapDefinition {
+    fn default() -> Self {
+        Self {
+            ground: GroundLayer::Biomes,
+            ground_tiles: HashMap::new(),
+            scatter_trees: true,
+            trees: HashSet::new(),
+            scatter_props: true,
+            props: HashMap::new(),
+            spawn_zones: vec![SpawnZone::Ring {
+                inner: 1000.,
+                outer: 2000.,
+            }],
+            pickup_points: Vec::new(),
+            bounds: None,
+        }
+    }
+}
+
+impl MapDefinition {
+    // Checks everything spawning samples or clamps against, which would panic
+    // on an empty range.
+    pub fn validate(&self) -> Result<(), String> {
+        for zone in &self.spawn_zones {
+            zone.validate()?;
+        }
+        if !self.pickup_points.iter().all(|point| point.is_finite()) {
+            return Err("a pickup point is not finite".into());
+        }
+        if self.bounds.is_some_and(|bounds| !is_solid(&bounds)) {
+            return Err("the bounds are empty, inverted or not finite".into());
+        }
+        Ok(())
+    }
+
+    pub fn ground_at(&self, seed: u64, tile: IVec2) -> Ground {
+        if let Some(ground) = self.ground_tiles.get(&tile) {
+            return *ground;
+        }
+        match self.ground {
+            GroundLayer::Noise => chunks::ground_at(seed, tile),
+            GroundLayer::Biomes => pick_weighted(
+                self.biome_at(seed, tile).def().ground,
+                chunks::patch_noise(seed, tile),
+            ),
+            GroundLayer::Uniform(ground) => ground,
+        }
+    }
+
+    pub fn biome_at(&self, seed: u64, tile: IVec2) -> Biome {
+        match self.ground {
+            GroundLayer::Biomes => biomes::biome_at(seed, tile),
+            _ => Biome::Meadow,
+        }
+    }
+
+    pub fn tree_at(&self, seed: u64, tile: IVec2) -> bool {
+        self.trees.contains(&tile)
+            || (self.scatter_trees
+                && self.ground_at(seed, tile) == Ground::Grass
+                && trees::scattered_tree_at(
+                    seed,
+                    tile,
+                    self.biome_at(seed, tile).def().tree_density,
+                ))
+    }
+
+    pub fn prop_at(&self, seed: u64, tile: IVec2) -> Option<PropKind> {
+        if let Some(kind) = self.props.get(&tile) {
+            return Some(*kind);
+        }
+        if !self.scatter_props || self.tree_at(seed, tile) {
+            return None;
+        }
+        props::scattered_prop_at(seed, tile)
+    }
+
+    // Enemies appear in one of the spawn zones, picked at random when there is
+    // more than one, and always inside the bounds. Points beyond a wall are
+    // pushed onto it, or onto the opposite side of the ring when that would put
+    // them on top of a player standing by the wall.
+    pub fn spawn_point(&self, rng: &mut SmallRng, player: Vec2) -> Vec2 {
+        let zone = match self.spawn_zones.len() {
+            0 => return player,
+            1 => &self.spawn_zones[0],
+            n => &self.spawn_zones[rng.gen_range(0..n)],
+        };
+        let point = zone.sample(rng, player);
+        let clamped = self.clamp_to_bounds(point);
+        if let SpawnZone::Ring { inner, .. } = zone {
+            let distance = clamped.distance(player);
+            if distance < *inner {
+                let mirrored = self.clamp_to_bounds(player * 2. - point);
+                if mirrored.distance(player) > distance {
+                    return mirrored;
+                }
+            }
+        }
+        clamped
+    }
+
+    pub fn pickup_point(&self, rng: &mut SmallRng, player: Vec2) -> Vec2 {
+        if self.pickup_points.is_empty() {
+            return self.spawn_point(rng, player);
+        }
+        self.pickup_points[rng.gen_range(0..self.pickup_points.len())]
+    }
+
+    pub fn in_bounds(&self, point: Vec2) -> bool {
+        self.bounds.is_none_or(|bounds| bounds.contains(point))
+    }
+
+    pub fn clamp_to_bounds(&self, point: Vec2) -> Vec2 {
+        match self.bounds {
+            Some(bounds) => point.clamp(bounds.min, bounds.max),
+            None => point,
+        }
+    }
+
+    // Keeps a circle of `radius` entirely inside the walls.
+    pub fn confine(&self, point: Vec2, radius: f32) -> Vec2 {
+        match self.bounds {
+            Some(bounds) => {
+                let inner = bounds.inflate(-radius);
+                point.clamp(inner.min, inner.max.max(inner.min))
+            }
+            None => point,
+        }
+    }
+}
+
+impl RonAsset for MapDefinition {
+    const EXTENSIONS: &'static [&'static str] = &["map.ron"];
+}
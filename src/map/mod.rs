@@ -0,0 +1,133 @@
+pub mod biomes;
+pub mod chunks;
+pub mod definition;
+pub mod noise;
+pub mod trees;
+pub mod walls;
+
+use bevy::prelude::*;
+use chunks::*;
+use definition::MapDefinition;
+use walls::update_walls;
+
+use crate::{
+    assets::RonAssetLoader, launch::LaunchOptions, rng::GameRng, LoadingAssets, NewRun,
+};
+
+pub const DEFAULT_MAP: &str = "maps/default.map.ron";
+
+pub struct MapPlugin;
+
+impl Plugin for MapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<MapDefinition>()
+            .init_asset_loader::<RonAssetLoader<MapDefinition>>()
+            .init_resource::<MapConfig>()
+            .init_resource::<MapSeed>()
+            .init_resource::<LoadedChunks>()
+            .add_systems(PreStartup, load_map)
+            .add_systems(NewRun, sync_map_seed)
+            .add_systems(PreUpdate, sync_active_map)
+            .add_systems(
+                Update,
+                (
+                    update_chunks,
+                    update_walls.run_if(resource_changed::<ActiveMap>),
+                ),
+            );
+    }
+}
+
+// Ground is streamed in square chunks of `chunk_size` tiles around the player;
+// chunks more than `view_distance` chunks away are despawned.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct MapConfig {
+    pub tile_size: f32,
+    pub chunk_size: u32,
+    pub view_distance: i32,
+}
+
+impl Default for MapConfig {
+    fn default() -> Self {
+        Self {
+            tile_size: 32.,
+            chunk_size: 16,
+            view_distance: 2,
+        }
+    }
+}
+
+impl MapConfig {
+    pub fn chunk_world_size(&self) -> f32 {
+        self.tile_size * self.chunk_size as f32
+    }
+
+    pub fn chunk_at(&self, position: Vec2) -> IVec2 {
+        (position / self.chunk_world_size()).floor().as_ivec2()
+    }
+
+    pub fn tile_at(&self, position: Vec2) -> IVec2 {
+        (position / self.tile_size).floor().as_ivec2()
+    }
+}
+
+// The layout only depends on this seed, taken from the game seed at the start
+// of each run.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MapSeed(pub u64);
+
+fn sync_map_seed(rng: Res<GameRng>, mut seed: ResMut<MapSeed>) {
+    seed.set_if_neq(MapSeed(rng.seed()));
+}
+
+// The map in play. Until its file has loaded, or if it fails to, this holds the
+// built-in endless map.
+#[derive(Resource)]
+pub struct ActiveMap {
+    pub handle: Handle<MapDefinition>,
+    pub definition: MapDefinition,
+}
+
+fn load_map(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    options: Res<LaunchOptions>,
+    mut loading: ResMut<LoadingAssets>,
+) {
+    let handle = match &options.map {
+        Some(path) => asset_server.load(path.clone()),
+        None => asset_server.load(DEFAULT_MAP),
+    };
+    loading.0.push(handle.clone().untyped());
+    commands.insert_resource(ActiveMap {
+        handle,
+        definition: MapDefinition::default(),
+    });
+}
+
+fn sync_active_map(
+    mut events: EventReader<AssetEvent<MapDefinition>>,
+    maps: Res<Assets<MapDefinition>>,
+    mut active: ResMut<ActiveMap>,
+) {
+    for event in events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) =
+            event
+        else {
+            continue;
+        };
+        if *id != active.handle.id() {
+            continue;
+        }
+        let Some(definition) = maps.get(*id) else {
+            continue;
+        };
+        // A bad file leaves the map in play, which before the first good load
+        // is the built-in endless one.
+        if let Err(problem) = definition.validate() {
+            warn!("map is invalid, keeping the previous one: {problem}");
+            continue;
+        }
+        active.definition = definition.clone();
+    }
+}
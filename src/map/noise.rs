@@ -0,0 +1,26 @@
+use bevy::prelude::*;
+
+pub fn hash(seed: u64, x: i32, y: i32) -> f32 {
+    let mut h = seed
+        ^ (x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (y as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xFF51_AFD7_ED55_8CCD);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xC4CE_B9FE_1A85_EC53);
+    h ^= h >> 33;
+    (h >> 40) as f32 / (1u64 << 24) as f32
+}
+
+// Smoothly interpolated random values on an integer lattice, in 0..1. The same
+// seed and point always give the same value.
+pub fn value_noise(seed: u64, point: Vec2) -> f32 {
+    let cell = point.floor();
+    let t = point - cell;
+    let t = t * t * (3. - 2. * t);
+    let (x, y) = (cell.x as i32, cell.y as i32);
+
+    let bottom = hash(seed, x, y).lerp(hash(seed, x + 1, y), t.x);
+    let top = hash(seed, x, y + 1).lerp(hash(seed, x + 1, y + 1), t.x);
+    bottom.lerp(top, t.y)
+}
@@ -0,0 +1,71 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use super::{noise::hash, ActiveMap, MapConfig, MapSeed};
+
+const TREE_SALT: u64 = 0x7EE5;
+// Keeps the area around the spawn point clear so a run never starts in a tree.
+const CLEARING_RADIUS: i32 = 4;
+pub const TREE_RADIUS: f32 = 12.;
+
+// Scattered trees are a pure function of the seed and tile, so collision never
+// depends on which chunks happen to be spawned. The map decides which ground
+// they can grow on and how densely.
+pub fn scattered_tree_at(seed: u64, tile: IVec2, density: f32) -> bool {
+    tile.abs().max_element() > CLEARING_RADIUS
+        && hash(seed ^ TREE_SALT, tile.x, tile.y) < density
+}
+
+#[derive(SystemParam)]
+pub struct Trees<'w> {
+    config: Res<'w, MapConfig>,
+    seed: Res<'w, MapSeed>,
+    map: Res<'w, ActiveMap>,
+}
+
+impl Trees<'_> {
+    pub fn tile_at(&self, position: Vec2) -> IVec2 {
+        self.config.tile_at(position)
+    }
+
+    pub fn tile_centre(&self, tile: IVec2) -> Vec2 {
+        (tile.as_vec2() + 0.5) * self.config.tile_size
+    }
+
+    pub fn tile_size(&self) -> f32 {
+        self.config.tile_size
+    }
+
+    // Whether a tile is taken up by a tree or lies beyond the arena walls.
+    pub fn blocked(&self, tile: IVec2) -> bool {
+        self.map.definition.tree_at(self.seed.0, tile)
+            || !self.map.definition.in_bounds(self.tile_centre(tile))
+    }
+
+    // Moves a circle of `radius` at `position` out of any tree it overlaps,
+    // keeping the movement along the trunk so actors slide around it, and back
+    // inside the arena walls if the map has any.
+    pub fn push_out(&self, position: Vec2, radius: f32) -> Vec2 {
+        let seed = self.seed.0;
+        let reach = ((radius + TREE_RADIUS) / self.config.tile_size).ceil() as i32;
+        let tile = self.tile_at(position);
+        let mut position = position;
+
+        for y in -reach..=reach {
+            for x in -reach..=reach {
+                let neighbour = tile + IVec2::new(x, y);
+                if !self.map.definition.tree_at(seed, neighbour) {
+                    continue;
+                }
+                let centre = self.tile_centre(neighbour);
+                let offset = position - centre;
+                let min_distance = radius + TREE_RADIUS;
+                if offset.length_squared() >= min_distance * min_distance {
+                    continue;
+                }
+                let normal = offset.try_normalize().unwrap_or(Vec2::Y);
+                position = centre + normal * min_distance;
+            }
+        }
+        self.map.definition.confine(position, radius)
+    }
+}
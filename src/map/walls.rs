@@ -0,0 +1,55 @@
+use bevy::prelude::*;
+
+use super::ActiveMap;
+
+const WALL_THICKNESS: f32 = 32.;
+const WALL_Z: f32 = 2.;
+const WALL_COLOR: Color = Color::srgb(0.16, 0.12, 0.1);
+
+#[derive(Component)]
+pub struct Wall;
+
+// Walls are drawn just outside the bounds, which collision already keeps every
+// actor within.
+pub fn update_walls(
+    mut commands: Commands,
+    map: Res<ActiveMap>,
+    wall_query: Query<Entity, With<Wall>>,
+) {
+    for entity in wall_query.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    let Some(bounds) = map.definition.bounds else {
+        return;
+    };
+
+    let half = WALL_THICKNESS / 2.;
+    let centre = bounds.center();
+    let span = bounds.inflate(WALL_THICKNESS).size();
+    let sides = [
+        (
+            centre.with_y(bounds.max.y + half),
+            Vec2::new(span.x, WALL_THICKNESS),
+        ),
+        (
+            centre.with_y(bounds.min.y - half),
+            Vec2::new(span.x, WALL_THICKNESS),
+        ),
+        (
+            centre.with_x(bounds.min.x - half),
+            Vec2::new(WALL_THICKNESS, span.y),
+        ),
+        (
+            centre.with_x(bounds.max.x + half),
+            Vec2::new(WALL_THICKNESS, span.y),
+        ),
+    ];
+    for (position, size) in sides {
+        commands.spawn((
+            Sprite::from_color(WALL_COLOR, size),
+            Transform::from_translation(position.extend(WALL_Z)),
+            Wall,
+        ));
+    }
+}
@@ -1,97 +1,117 @@
-use bevy::prelude::*;
-use rand::{rngs::SmallRng, Rng, SeedableRng};
-
-use crate::{
-    assets::Images, player::components::Player, random_point_within_radius, CollisionSet,
-    GameState, SpawnSet,
-};
-
-#[derive(Component)]
-pub struct Pickup;
-
-#[derive(Resource)]
-pub struct SpawnTimer {
-    pub countdown: Timer,
-}
-
-impl SpawnTimer {
-    pub fn new() -> Self {
-        let mut rng = SmallRng::from_entropy();
-        Self {
-            countdown: Timer::from_seconds(
-                rng.gen_range(10.0..30.),
-                TimerMode::Repeating,
-            ),
-        }
-    }
-}
-
-pub struct PickupPlugin;
-
-impl Plugin for PickupPlugin {
-    fn build(&self, app: &mut App) {
-        app.insert_resource(SpawnTimer::new()).add_systems(
-            Update,
-            (
-                spawn_pickups
-                    .in_set(SpawnSet)
-                    .run_if(in_state(GameState::Running)),
-                pickup_collision
-                    .in_set(CollisionSet)
-                    .run_if(in_state(GameState::Running)),
-            ),
-        );
-    }
-}
-
-pub fn spawn_pickups(
-    mut commands: Commands,
-    icon: Res<Images>,
-    mut timer: ResMut<SpawnTimer>,
-    time: Res<Time>,
-    player_query: Query<&Transform, With<Player>>,
-    state: Res<State<GameState>>,
-) {
-    if *state != GameState::Running {
-        return;
-    }
-    timer.countdown.tick(time.delta());
-    let Ok(&player_transform) = player_query.get_single() else {
-        return;
-    };
-    let texture_hanlde = icon.health_potion.clone();
-    if timer.countdown.finished() {
-        let mut rng = SmallRng::from_entropy();
-
-        let x_start = player_transform.translation.x;
-        let y_start = player_transform.translation.y;
-        let (x_offset, y_offset) = random_point_within_radius(&mut rng, x_start, y_start);
-
-        commands.spawn((
-            Sprite::from_image(texture_hanlde.clone()),
-            Transform::from_xyz(x_start + x_offset, y_start + y_offset, 1.),
-            Pickup,
-        ));
-    }
-}
-
-pub fn pickup_collision(
-    mut commands: Commands,
-    mut player_query: Query<(&mut Player, &Transform), With<Player>>,
-    pickup_query: Query<(Entity, &Transform), With<Pickup>>,
-) {
-    let Ok((mut player, player_transform)) = player_query.get_single_mut() else {
-        return;
-    };
-
-    for (entity, transform) in pickup_query.iter() {
-        let distance = Vec2::new(
-            player_transform.translation.x - transform.translation.x,
-            player_transform.translation.y - transform.translation.y,
-        );
-        if distance.length() <= 32. {
-            player.health = (player.health + 25.).min(player.max_health);
-            commands.entity(entity).despawn();
-        }
-    }
-}
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    assets::Images,
+    enemy::systems::enemy_attack,
+    map::ActiveMap,
+    player::components::Player,
+    rng::{GameRng, RngStream},
+    spatial::SpatialGrid,
+    CollisionSet, GameState, NewRun, RunScoped, SpawnSet,
+};
+
+#[derive(Component)]
+#[require(RunScoped)]
+pub struct Pickup;
+
+#[derive(Resource)]
+pub struct SpawnTimer {
+    pub countdown: Timer,
+}
+
+impl SpawnTimer {
+    pub fn new(rng: &mut impl Rng) -> Self {
+        Self {
+            countdown: Timer::from_seconds(
+                rng.gen_range(10.0..30.),
+                TimerMode::Repeating,
+            ),
+        }
+    }
+}
+
+pub struct PickupPlugin;
+
+impl Plugin for PickupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(NewRun, setup_spawn_timer).add_systems(
+            FixedUpdate,
+            (
+                spawn_pickups
+                    .in_set(SpawnSet)
+                    .run_if(in_state(GameState::Running)),
+                pickup_collision
+                    .in_set(CollisionSet)
+                    .after(enemy_attack)
+                    .run_if(in_state(GameState::Running)),
+            ),
+        );
+    }
+}
+
+fn setup_spawn_timer(mut commands: Commands, mut rng: ResMut<GameRng>) {
+    commands.insert_resource(SpawnTimer::new(rng.stream(RngStream::Pickups)));
+}
+
+pub fn spawn_pickups(
+    mut commands: Commands,
+    icon: Res<Images>,
+    mut timer: ResMut<SpawnTimer>,
+    time: Res<Time>,
+    player_query: Query<&Transform, With<Player>>,
+    state: Res<State<GameState>>,
+    mut rng: ResMut<GameRng>,
+    map: Res<ActiveMap>,
+) {
+    if *state != GameState::Running {
+        return;
+    }
+    timer.countdown.tick(time.delta());
+    let Ok(&player_transform) = player_query.get_single() else {
+        return;
+    };
+    let texture_hanlde = icon.health_potion.clone();
+    if timer.countdown.finished() {
+        let position = map.definition.pickup_point(
+            rng.stream(RngStream::Pickups),
+            player_transform.translation.truncate(),
+        );
+
+        commands.spawn(pickup_bundle(texture_hanlde.clone(), position.extend(1.)));
+    }
+}
+
+pub fn pickup_bundle(image: Handle<Image>, translation: Vec3) -> impl Bundle {
+    (
+        Sprite::from_image(image),
+        Transform::from_translation(translation),
+        Pickup,
+    )
+}
+
+pub fn pickup_collision(
+    mut commands: Commands,
+    mut player_query: Query<(&mut Player, &Transform), With<Player>>,
+    pickup_query: Query<&Transform, With<Pickup>>,
+    grid: Res<SpatialGrid>,
+) {
+    let Ok((mut player, player_transform)) = player_query.get_single_mut() else {
+        return;
+    };
+
+    let position = player_transform.translation.truncate();
+    for entity in grid.entities_within(position, 32.) {
+        let Ok(transform) = pickup_query.get(entity) else {
+            continue;
+        };
+        let distance = Vec2::new(
+            player_transform.translation.x - transform.translation.x,
+            player_transform.translation.y - transform.translation.y,
+        );
+        if distance.length() <= 32. {
+            player.health = (player.health + 25.).min(player.max_health);
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -1,6 +1,11 @@
 use bevy::prelude::*;
 
+use crate::{interpolation::Interpolated, RunScoped};
+
+pub const PLAYER_RADIUS: f32 = 12.;
+
 #[derive(Component)]
+#[require(Interpolated, RunScoped)]
 pub struct Player {
     pub health: f32,
     pub max_health: f32,
@@ -28,16 +33,33 @@ impl Player {
         }
     }
 
-    pub fn receive_damage(&mut self) {
-        self.health -= 1.2;
+    // Follows the same thresholds as levelling up during a run.
+    pub fn at_level(level: u32) -> Self {
+        let mut player = Self::new();
+        while player.level < level {
+            player.xp = player.next_level;
+            player.level += 1;
+            player.next_level = player.xp_to_next_level();
+        }
+        player
+    }
+
+    pub fn receive_damage(&mut self, damage: f32) {
+        self.health -= damage;
         self.recent_damage = true;
     }
 
     pub fn xp_to_next_level(&self) -> u32 {
-        2 * self.next_level
+        self.next_level.saturating_mul(2)
     }
 
     pub fn gain_xp(&mut self, xp: u32) {
-        self.xp += xp;
+        self.xp = self.xp.saturating_add(xp);
+    }
+}
+
+impl Default for Player {
+    fn default() -> Self {
+        Self::new()
     }
 }
@@ -6,13 +6,16 @@ use bevy::{color, prelude::*};
 #[derive(Component)]
 pub struct LevelUpMenu;
 
-#[derive(Component)]
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MenuButtonAction {
     AttackSpeed,
     MovementSpeed,
     Health,
 }
 
+#[derive(Event, Debug, Clone, Copy)]
+pub struct LevelUpChoice(pub MenuButtonAction);
+
 pub fn gain_level(
     mut player_query: Query<&mut Player>,
     mut game_state: ResMut<NextState<GameState>>,
@@ -54,6 +57,7 @@ pub fn spawn_levelup_menu(mut commands: Commands) {
             },
             BackgroundColor::from(Color::srgba(0., 0., 0., 0.5)),
             LevelUpMenu,
+            StateScoped(GameState::LevelUpScreen),
         ))
         .with_children(|parent| {
             parent
@@ -128,6 +132,17 @@ pub fn levelup_menu_action(
         (&Interaction, &MenuButtonAction),
         (Changed<Interaction>, With<Button>),
     >,
+    mut choices: EventWriter<LevelUpChoice>,
+) {
+    for (interaction, menu_button_action) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            choices.send(LevelUpChoice(*menu_button_action));
+        }
+    }
+}
+
+pub fn apply_level_up_choice(
+    mut choices: EventReader<LevelUpChoice>,
     mut player_query: Query<&mut Player>,
     mut game_state: ResMut<NextState<GameState>>,
 ) {
@@ -135,32 +150,21 @@ pub fn levelup_menu_action(
         return;
     };
 
-    for (interaction, menu_button_action) in &interaction_query {
-        if *interaction == Interaction::Pressed {
-            match menu_button_action {
-                MenuButtonAction::AttackSpeed => {
-                    player.attack_speed_mod += 0.1;
-                    game_state.set(GameState::Running);
-                }
-                MenuButtonAction::MovementSpeed => {
-                    player.movement_speed_mod += 0.25;
-                    game_state.set(GameState::Running);
-                }
-                MenuButtonAction::Health => {
-                    player.max_health += 25.0;
-                    player.health = player.max_health;
-                    game_state.set(GameState::Running);
-                }
+    for LevelUpChoice(menu_button_action) in choices.read() {
+        match menu_button_action {
+            MenuButtonAction::AttackSpeed => {
+                player.attack_speed_mod += 0.1;
+                game_state.set(GameState::Running);
+            }
+            MenuButtonAction::MovementSpeed => {
+                player.movement_speed_mod += 0.25;
+                game_state.set(GameState::Running);
+            }
+            MenuButtonAction::Health => {
+                player.max_health += 25.0;
+                player.health = player.max_health;
+                game_state.set(GameState::Running);
             }
         }
     }
 }
-
-pub fn despawn_levelup_menu(
-    mut commands: Commands,
-    query: Query<Entity, With<LevelUpMenu>>,
-) {
-    for entity in query.iter() {
-        commands.entity(entity).despawn_recursive();
-    }
-}
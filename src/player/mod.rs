@@ -1,44 +1,59 @@
 pub mod components;
-mod levelup;
+pub mod levelup;
 pub mod systems;
 use bevy::prelude::*;
 use components::Player;
 use levelup::*;
 use systems::*;
 
-use crate::GameState;
+use crate::{
+    launch::god_mode,
+    replay::ReplayPlayback,
+    settings::{Action, Settings},
+    DespawnSet, GameState,
+};
 
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::Running), setup_player)
+        app.add_event::<LevelUpChoice>()
+            .add_systems(OnEnter(GameState::Running), setup_player)
+            .add_systems(
+                FixedUpdate,
+                (kill_player, gain_level, dirty_xp.run_if(god_mode))
+                    .chain()
+                    .after(DespawnSet)
+                    .run_if(in_state(GameState::Running)),
+            )
             .add_systems(
                 Update,
-                (
-                    kill_player,
-                    damage_audio_cooldown,
-                    color_change_cooldown,
-                    gain_level,
-                    dirty_xp,
-                )
+                (damage_audio_cooldown, color_change_cooldown)
                     .run_if(in_state(GameState::Running)),
             )
             .add_systems(OnEnter(GameState::LevelUpScreen), spawn_levelup_menu)
             .add_systems(
                 Update,
-                levelup_menu_action.run_if(in_state(GameState::LevelUpScreen)),
-            )
-            .add_systems(OnExit(GameState::LevelUpScreen), despawn_levelup_menu);
+                (
+                    levelup_menu_action.run_if(not(resource_exists::<ReplayPlayback>)),
+                    apply_level_up_choice,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::LevelUpScreen)),
+            );
     }
 }
 
-fn dirty_xp(input: Res<ButtonInput<KeyCode>>, mut player_query: Query<&mut Player>) {
+fn dirty_xp(
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    mut player_query: Query<&mut Player>,
+) {
     let Ok(mut player) = player_query.get_single_mut() else {
         return;
     };
 
-    if input.pressed(KeyCode::NumpadAdd) {
+    if settings.key_bindings.pressed(&input, Action::DebugXp) {
         player.xp += 100;
     }
 }
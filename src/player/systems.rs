@@ -1,10 +1,11 @@
 use super::components::*;
-use crate::{assets::*, GameState};
+use crate::{assets::*, launch::LaunchOptions, GameState};
 use bevy::{color, prelude::*};
 
 pub fn setup_player(
     mut commands: Commands,
     icons: Res<Images>,
+    options: Res<LaunchOptions>,
     player_query: Query<&Player>,
 ) {
     if player_query.iter().count() > 0 {
@@ -14,7 +15,7 @@ pub fn setup_player(
     commands.spawn((
         Sprite::from_image(icons.samurai.clone()),
         Transform::from_xyz(0., 0., 1.),
-        Player::new(),
+        Player::at_level(options.start_level.unwrap_or(1)),
     ));
 }
 
@@ -0,0 +1,267 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    animation::{AnimationIndices, AnimationTimerOnce},
+    assets::Images,
+    map::{
+        chunks::{update_chunks, LoadedChunks},
+        noise::hash,
+        ActiveMap, MapConfig, MapSeed,
+    },
+    pickups::pickup_bundle,
+    rng::{GameRng, RngStream},
+    DespawnSet, GameState, NewRun, RunScoped,
+};
+
+const PROP_SALT: u64 = 0x960B;
+const PROP_DENSITY: f32 = 0.004;
+const PROP_Z: f32 = 0.5;
+const FRAMES: u32 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PropKind {
+    Crate,
+    Lantern,
+    Urn,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Loot {
+    Nothing,
+    HealthPotion,
+}
+
+impl PropKind {
+    pub fn health(self) -> f32 {
+        match self {
+            PropKind::Crate => 30.,
+            PropKind::Lantern => 10.,
+            PropKind::Urn => 20.,
+        }
+    }
+
+    // Weighted entries, rolled once when the prop breaks.
+    pub fn drop_table(self) -> &'static [(Loot, u32)] {
+        match self {
+            PropKind::Crate => &[(Loot::Nothing, 1), (Loot::HealthPotion, 1)],
+            PropKind::Lantern => &[(Loot::Nothing, 3), (Loot::HealthPotion, 1)],
+            PropKind::Urn => &[(Loot::Nothing, 1), (Loot::HealthPotion, 3)],
+        }
+    }
+
+    fn row(self) -> usize {
+        match self {
+            PropKind::Crate => 0,
+            PropKind::Lantern => 1,
+            PropKind::Urn => 2,
+        }
+    }
+}
+
+pub fn roll_loot(table: &[(Loot, u32)], rng: &mut impl Rng) -> Loot {
+    let total: u32 = table.iter().map(|(_, weight)| weight).sum();
+    let mut roll = rng.gen_range(0..total);
+    for (loot, weight) in table {
+        if roll < *weight {
+            return *loot;
+        }
+        roll -= weight;
+    }
+    Loot::Nothing
+}
+
+// Like trees, scattered props only depend on the seed and tile. The map decides
+// where they are allowed.
+pub fn scattered_prop_at(seed: u64, tile: IVec2) -> Option<PropKind> {
+    let roll = hash(seed ^ PROP_SALT, tile.x, tile.y);
+    if roll >= PROP_DENSITY {
+        return None;
+    }
+    Some(match (roll / PROP_DENSITY * 3.) as u32 {
+        0 => PropKind::Crate,
+        1 => PropKind::Lantern,
+        _ => PropKind::Urn,
+    })
+}
+
+#[derive(Component)]
+#[require(RunScoped)]
+pub struct Prop {
+    pub kind: PropKind,
+    pub health: f32,
+}
+
+// The tile a prop, or what is left of it, stands on.
+#[derive(Component)]
+pub struct PropTile(pub IVec2);
+
+// Props broken this run, which stay broken when their chunk streams back in.
+#[derive(Resource, Default)]
+pub struct BrokenProps(pub HashSet<IVec2>);
+
+#[derive(Resource, Default)]
+struct PropChunks(HashSet<IVec2>);
+
+// The atlas layout of the prop sheet, shared by every prop.
+#[derive(Resource)]
+struct PropLayout(Handle<TextureAtlasLayout>);
+
+pub struct PropPlugin;
+
+impl Plugin for PropPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BrokenProps>()
+            .init_resource::<PropChunks>()
+            .add_systems(Startup, setup_prop_layout)
+            .add_systems(NewRun, reset_props)
+            .add_systems(
+                Update,
+                stream_props
+                    .after(update_chunks)
+                    .run_if(not(in_state(GameState::Loading))),
+            )
+            .add_systems(
+                FixedUpdate,
+                break_props
+                    .in_set(DespawnSet)
+                    .run_if(in_state(GameState::Running)),
+            );
+    }
+}
+
+fn reset_props(mut broken: ResMut<BrokenProps>, mut chunks: ResMut<PropChunks>) {
+    broken.0.clear();
+    chunks.0.clear();
+}
+
+fn setup_prop_layout(
+    mut commands: Commands,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    commands.insert_resource(PropLayout(layouts.add(TextureAtlasLayout::from_grid(
+        UVec2::new(32, 32),
+        FRAMES,
+        3,
+        None,
+        None,
+    ))));
+}
+
+// Props follow the streamed chunks. They are spawned at the top level rather
+// than under the chunk so attacks can collide with their `Transform` directly.
+#[allow(clippy::too_many_arguments)]
+fn stream_props(
+    mut commands: Commands,
+    loaded: Res<LoadedChunks>,
+    mut prop_chunks: ResMut<PropChunks>,
+    prop_query: Query<(Entity, &PropTile)>,
+    broken: Res<BrokenProps>,
+    config: Res<MapConfig>,
+    seed: Res<MapSeed>,
+    map: Res<ActiveMap>,
+    icons: Res<Images>,
+    layout: Res<PropLayout>,
+) {
+    let size = config.chunk_size as i32;
+    if (map.is_changed() && !map.is_added())
+        || (config.is_changed() && !config.is_added())
+    {
+        prop_chunks.0.clear();
+    }
+
+    let stale: HashSet<IVec2> = prop_chunks
+        .0
+        .iter()
+        .filter(|coord| !loaded.0.contains_key(coord))
+        .copied()
+        .collect();
+    for (entity, tile) in prop_query.iter() {
+        let chunk = tile.0.div_euclid(IVec2::splat(size));
+        if !prop_chunks.0.contains(&chunk) || stale.contains(&chunk) {
+            commands.entity(entity).despawn();
+        }
+    }
+    prop_chunks.0.retain(|coord| !stale.contains(coord));
+
+    let mut new_chunks: Vec<IVec2> = loaded
+        .0
+        .keys()
+        .filter(|coord| !prop_chunks.0.contains(coord))
+        .copied()
+        .collect();
+    // Spawn in a fixed order so props that break on the same tick roll their
+    // loot in the same order every run.
+    new_chunks.sort_by_key(|coord| (coord.y, coord.x));
+    if new_chunks.is_empty() {
+        return;
+    }
+
+    for coord in new_chunks {
+        prop_chunks.0.insert(coord);
+        for y in 0..size {
+            for x in 0..size {
+                let tile = coord * size + IVec2::new(x, y);
+                let position = (tile.as_vec2() + 0.5) * config.tile_size;
+                if broken.0.contains(&tile) || !map.definition.in_bounds(position) {
+                    continue;
+                }
+                let Some(kind) = map.definition.prop_at(seed.0, tile) else {
+                    continue;
+                };
+                commands.spawn((
+                    Sprite {
+                        image: icons.props.clone(),
+                        texture_atlas: Some(TextureAtlas {
+                            layout: layout.0.clone(),
+                            index: kind.row() * FRAMES as usize,
+                        }),
+                        ..default()
+                    },
+                    Transform::from_translation(position.extend(PROP_Z)),
+                    Prop {
+                        kind,
+                        health: kind.health(),
+                    },
+                    PropTile(tile),
+                ));
+            }
+        }
+    }
+}
+
+pub fn break_props(
+    mut commands: Commands,
+    prop_query: Query<(Entity, &Prop, &PropTile, &Transform)>,
+    mut broken: ResMut<BrokenProps>,
+    mut rng: ResMut<GameRng>,
+    icons: Res<Images>,
+) {
+    for (entity, prop, tile, transform) in prop_query.iter() {
+        if prop.health > 0. {
+            continue;
+        }
+
+        broken.0.insert(tile.0);
+        let first = prop.kind.row() * FRAMES as usize;
+        commands.entity(entity).remove::<Prop>().insert((
+            AnimationTimerOnce(Timer::from_seconds(0.1, TimerMode::Repeating)),
+            AnimationIndices {
+                first,
+                last: first + FRAMES as usize - 1,
+                current: first,
+            },
+        ));
+
+        let loot = roll_loot(prop.kind.drop_table(), rng.stream(RngStream::Props));
+        if loot == Loot::HealthPotion {
+            commands.spawn(pickup_bundle(
+                icons.health_potion.clone(),
+                transform.translation.with_z(1.),
+            ));
+        }
+    }
+}
@@ -0,0 +1,317 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use bevy::{input::InputSystem, prelude::*, time::TimeSystem, time::TimeUpdateStrategy};
+
+use crate::{
+    assets_loaded,
+    player::levelup::{LevelUpChoice, MenuButtonAction},
+    rng::GameRng,
+    settings::{Action, KeyBindings, Settings},
+    GameState,
+};
+
+const MAGIC: &[u8; 4] = b"BHRP";
+const VERSION: u8 = 4;
+// Ten hours at 60 frames a second, far longer than any real run. Anything past
+// it is a corrupt file rather than a recording.
+const MAX_FRAMES: usize = 10 * 60 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplayFrame {
+    pub delta: Duration,
+    pub actions: u16,
+    pub level_up: Option<MenuButtonAction>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub timestep: Duration,
+    pub frames: Vec<ReplayFrame>,
+}
+
+impl Replay {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::decode(&fs::read(path)?)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.encode())
+    }
+
+    // Identical consecutive frames are stored once with a repeat count, which
+    // keeps idle stretches and headless runs down to a handful of bytes.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut runs: Vec<(u32, ReplayFrame)> = Vec::new();
+        for frame in &self.frames {
+            match runs.last_mut() {
+                Some((count, last)) if last == frame => *count += 1,
+                _ => runs.push((1, *frame)),
+            }
+        }
+
+        let mut bytes = Vec::with_capacity(25 + runs.len() * 11);
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.extend_from_slice(&(self.timestep.as_nanos() as u64).to_le_bytes());
+        bytes.extend_from_slice(&(runs.len() as u32).to_le_bytes());
+        for (count, frame) in runs {
+            bytes.extend_from_slice(&count.to_le_bytes());
+            let nanos = frame.delta.as_nanos().min(u32::MAX as u128) as u32;
+            bytes.extend_from_slice(&nanos.to_le_bytes());
+            bytes.extend_from_slice(&frame.actions.to_le_bytes());
+            bytes.push(encode_choice(frame.level_up));
+        }
+        bytes
+    }
+
+    pub fn decode(mut bytes: &[u8]) -> io::Result<Self> {
+        let mut magic = [0; 4];
+        bytes.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("not a bevy_hell replay"));
+        }
+        let version = read_u8(&mut bytes)?;
+        if version != VERSION {
+            return Err(invalid(&format!("unsupported replay version {version}")));
+        }
+
+        let seed = u64::from_le_bytes(read_array(&mut bytes)?);
+        let timestep = Duration::from_nanos(u64::from_le_bytes(read_array(&mut bytes)?));
+        let n_runs = u32::from_le_bytes(read_array(&mut bytes)?);
+        let mut frames = Vec::new();
+        for _ in 0..n_runs {
+            let count = u32::from_le_bytes(read_array(&mut bytes)?);
+            let nanos = u32::from_le_bytes(read_array(&mut bytes)?);
+            let actions = u16::from_le_bytes(read_array(&mut bytes)?);
+            let level_up = decode_choice(read_u8(&mut bytes)?)?;
+            let frame = ReplayFrame {
+                delta: Duration::from_nanos(nanos as u64),
+                actions,
+                level_up,
+            };
+            if frames.len().saturating_add(count as usize) > MAX_FRAMES {
+                return Err(invalid("replay is longer than any run could be"));
+            }
+            frames.extend(std::iter::repeat_n(frame, count as usize));
+        }
+
+        Ok(Self {
+            seed,
+            timestep,
+            frames,
+        })
+    }
+}
+
+fn encode_choice(choice: Option<MenuButtonAction>) -> u8 {
+    match choice {
+        None => 0,
+        Some(MenuButtonAction::AttackSpeed) => 1,
+        Some(MenuButtonAction::MovementSpeed) => 2,
+        Some(MenuButtonAction::Health) => 3,
+    }
+}
+
+fn decode_choice(byte: u8) -> io::Result<Option<MenuButtonAction>> {
+    match byte {
+        0 => Ok(None),
+        1 => Ok(Some(MenuButtonAction::AttackSpeed)),
+        2 => Ok(Some(MenuButtonAction::MovementSpeed)),
+        3 => Ok(Some(MenuButtonAction::Health)),
+        _ => Err(invalid(&format!("unknown level up choice {byte}"))),
+    }
+}
+
+fn read_u8(bytes: &mut &[u8]) -> io::Result<u8> {
+    Ok(read_array::<1>(bytes)?[0])
+}
+
+fn read_array<const N: usize>(bytes: &mut &[u8]) -> io::Result<[u8; N]> {
+    let mut buf = [0; N];
+    bytes.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+// Actions rather than keys are recorded, so a replay still plays back after the
+// key bindings change.
+pub fn actions_to_mask(bindings: &KeyBindings, input: &ButtonInput<KeyCode>) -> u16 {
+    Action::ALL
+        .iter()
+        .enumerate()
+        .filter(|(_, action)| bindings.pressed(input, **action))
+        .fold(0, |mask, (i, _)| mask | 1 << i)
+}
+
+#[derive(Resource)]
+pub struct ReplayRecorder {
+    pub path: PathBuf,
+    pub replay: Replay,
+}
+
+#[derive(Resource)]
+pub struct ReplayPlayback {
+    pub replay: Replay,
+    pub next_frame: usize,
+    pub previous_actions: u16,
+    pub previous_strategy: Option<TimeUpdateStrategy>,
+}
+
+pub enum ReplayPlugin {
+    Record(PathBuf),
+    Playback(Replay),
+}
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        match self {
+            ReplayPlugin::Record(path) => {
+                app.insert_resource(ReplayRecorder {
+                    path: path.clone(),
+                    replay: Replay::default(),
+                })
+                .add_systems(Startup, record_settings)
+                .add_systems(Last, record_frame.run_if(not(in_state(GameState::Loading))))
+                .add_systems(OnEnter(GameState::GameOver), save_recording)
+                .add_systems(
+                    Last,
+                    save_recording
+                        .after(record_frame)
+                        .run_if(on_event::<AppExit>),
+                );
+            }
+            ReplayPlugin::Playback(replay) => {
+                app.insert_resource(GameRng::new(replay.seed))
+                    .insert_resource(Time::<Fixed>::from_duration(replay.timestep))
+                    .insert_resource(ReplayPlayback {
+                        replay: replay.clone(),
+                        next_frame: 0,
+                        previous_actions: 0,
+                        previous_strategy: None,
+                    })
+                    .add_systems(
+                        First,
+                        playback_time
+                            .before(TimeSystem)
+                            .run_if(resource_exists::<ReplayPlayback>.and(assets_loaded)),
+                    )
+                    .add_systems(
+                        PreUpdate,
+                        playback_input
+                            .after(InputSystem)
+                            .run_if(resource_exists::<ReplayPlayback>.and(assets_loaded)),
+                    );
+            }
+        }
+    }
+}
+
+fn record_settings(
+    mut recorder: ResMut<ReplayRecorder>,
+    rng: Res<GameRng>,
+    fixed_time: Res<Time<Fixed>>,
+) {
+    recorder.replay.seed = rng.seed();
+    recorder.replay.timestep = fixed_time.timestep();
+}
+
+fn record_frame(
+    mut recorder: ResMut<ReplayRecorder>,
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    time: Res<Time<Real>>,
+    mut choices: EventReader<LevelUpChoice>,
+) {
+    let level_up = choices.read().last().map(|choice| choice.0);
+    recorder.replay.frames.push(ReplayFrame {
+        delta: time.delta(),
+        actions: actions_to_mask(&settings.key_bindings, &input),
+        level_up,
+    });
+}
+
+fn save_recording(recorder: Res<ReplayRecorder>) {
+    match recorder.replay.save(&recorder.path) {
+        Ok(()) => info!(
+            "saved replay of {} frames to {}",
+            recorder.replay.frames.len(),
+            recorder.path.display()
+        ),
+        Err(err) => error!(
+            "failed to save replay to {}: {}",
+            recorder.path.display(),
+            err
+        ),
+    }
+}
+
+fn playback_time(
+    mut commands: Commands,
+    mut playback: ResMut<ReplayPlayback>,
+    mut strategy: ResMut<TimeUpdateStrategy>,
+) {
+    let Some(frame) = playback.replay.frames.get(playback.next_frame) else {
+        info!("replay finished, returning control to the player");
+        if let Some(previous) = playback.previous_strategy.take() {
+            *strategy = previous;
+        }
+        commands.remove_resource::<ReplayPlayback>();
+        return;
+    };
+
+    let previous = std::mem::replace(
+        &mut *strategy,
+        TimeUpdateStrategy::ManualDuration(frame.delta),
+    );
+    if playback.previous_strategy.is_none() {
+        playback.previous_strategy = Some(previous);
+    }
+}
+
+fn playback_input(
+    mut playback: ResMut<ReplayPlayback>,
+    mut input: ResMut<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    mut choices: EventWriter<LevelUpChoice>,
+) {
+    let Some(&frame) = playback.replay.frames.get(playback.next_frame) else {
+        return;
+    };
+
+    for (i, action) in Action::ALL.iter().enumerate() {
+        let Some(key) = settings.key_bindings.keys(*action).first() else {
+            continue;
+        };
+        let wanted = frame.actions & 1 << i != 0;
+        let was = playback.previous_actions & 1 << i != 0;
+
+        input.reset(*key);
+        if wanted {
+            input.press(*key);
+            if was {
+                input.clear_just_pressed(*key);
+            }
+        } else if was {
+            input.press(*key);
+            input.release(*key);
+            input.clear_just_pressed(*key);
+        }
+    }
+
+    if let Some(choice) = frame.level_up {
+        choices.send(LevelUpChoice(choice));
+    }
+
+    playback.previous_actions = frame.actions;
+    playback.next_frame += 1;
+}
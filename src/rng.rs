@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use rand::{rngs::SmallRng, SeedableRng};
+
+// Each stream is seeded from its own fixed id, so adding a stream never shifts
+// the sequence drawn by an existing one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RngStream {
+    EnemySpawn = 1,
+    EnemyMovement = 2,
+    Pickups = 3,
+    Audio = 4,
+    Props = 5,
+    Elites = 6,
+}
+
+#[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    streams: HashMap<RngStream, SmallRng>,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            streams: HashMap::new(),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // Every stream starts over from the seed, so each run with the same seed
+    // plays out the same way.
+    pub fn reset(&mut self) {
+        self.streams.clear();
+    }
+
+    pub fn stream(&mut self, stream: RngStream) -> &mut SmallRng {
+        let seed = self.seed;
+        self.streams.entry(stream).or_insert_with(|| {
+            SmallRng::seed_from_u64(
+                seed ^ (stream as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15),
+            )
+        })
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::new(rand::random())
+    }
+}
+
+pub fn log_seed(rng: Res<GameRng>) {
+    info!("RNG seed: {}", rng.seed());
+}
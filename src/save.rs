@@ -0,0 +1,306 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assets::Images,
+    attacks::AttackSpawner,
+    enemy::{
+        components::Boss,
+        elites::{Affix, Elite},
+        kinds::{EnemyKind, EnemyKinds},
+        systems::enemy_bundle,
+    },
+    finish_loading,
+    pickups::{pickup_bundle, Pickup},
+    player::components::Player,
+    props::BrokenProps,
+    replay::{ReplayPlayback, ReplayRecorder},
+    settings::{Action, Settings},
+    start_new_run, Enemy, GameState, GlobalStopwatch,
+};
+
+const SAVE_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedPlayer {
+    pub translation: Vec3,
+    pub health: f32,
+    pub max_health: f32,
+    pub xp: u32,
+    pub level: u32,
+    pub next_level: u32,
+    pub movement_speed_mod: f32,
+    pub attack_speed_mod: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedEnemy {
+    pub translation: Vec3,
+    pub health: f32,
+    // The name of the enemy's kind in the registry.
+    pub kind: String,
+    #[serde(default)]
+    pub affixes: Vec<Affix>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedAttackSpawner {
+    pub cooldown: Timer,
+    pub next_attack: Timer,
+    pub n_attacks: u32,
+    pub attack_i: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SaveGame {
+    pub version: u32,
+    pub elapsed: Duration,
+    pub player: SavedPlayer,
+    pub enemies: Vec<SavedEnemy>,
+    pub pickups: Vec<Vec3>,
+    pub attack_spawner: SavedAttackSpawner,
+    #[serde(default)]
+    pub broken_props: Vec<IVec2>,
+}
+
+impl SaveGame {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let save: Self = ron::from_str(&fs::read_to_string(path)?)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        if save.version != SAVE_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported save version {}", save.version),
+            ));
+        }
+        Ok(save)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let text = ron::ser::to_string_pretty(self, Default::default())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        fs::write(path, text)
+    }
+}
+
+pub fn default_save_path() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_default()
+        .join("bevy_hell")
+        .join("save.ron")
+}
+
+#[derive(Resource)]
+pub struct SaveFile(pub PathBuf);
+
+// A save read at startup, waiting for the first run to begin so it can be
+// restored on top of the freshly reset run state.
+#[derive(Resource)]
+struct PendingSave(SaveGame);
+
+pub struct SavePlugin {
+    pub path: PathBuf,
+}
+
+impl Default for SavePlugin {
+    fn default() -> Self {
+        Self {
+            path: default_save_path(),
+        }
+    }
+}
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SaveFile(self.path.clone()))
+            .add_systems(
+                Startup,
+                load_save.run_if(
+                    not(resource_exists::<ReplayPlayback>)
+                        .and(not(resource_exists::<ReplayRecorder>)),
+                ),
+            )
+            .add_systems(
+                Update,
+                resume_paused
+                    .after(finish_loading)
+                    .run_if(resource_exists::<PendingSave>),
+            )
+            .add_systems(
+                OnTransition {
+                    exited: GameState::Loading,
+                    entered: GameState::Paused,
+                },
+                (start_new_run, restore_save.after(start_new_run)),
+            )
+            .add_systems(Update, save_and_quit.run_if(in_state(GameState::Paused)));
+    }
+}
+
+fn load_save(mut commands: Commands, save_file: Res<SaveFile>) {
+    if !save_file.0.exists() {
+        return;
+    }
+
+    match SaveGame::load(&save_file.0) {
+        Ok(save) => {
+            // A save can only be resumed once.
+            if let Err(err) = fs::remove_file(&save_file.0) {
+                warn!("failed to remove save {}: {}", save_file.0.display(), err);
+            }
+            commands.insert_resource(PendingSave(save));
+        }
+        Err(err) => warn!("ignoring save {}: {}", save_file.0.display(), err),
+    }
+}
+
+// A resumed run goes straight from loading to paused, so not a single tick
+// plays before the player is ready.
+fn resume_paused(mut game_state: ResMut<NextState<GameState>>) {
+    if matches!(*game_state, NextState::Pending(GameState::Running)) {
+        game_state.set(GameState::Paused);
+    }
+}
+
+fn restore_save(
+    mut commands: Commands,
+    pending: Res<PendingSave>,
+    icons: Res<Images>,
+    kinds: Res<EnemyKinds>,
+    mut spawner: ResMut<AttackSpawner>,
+    mut stopwatch: ResMut<GlobalStopwatch>,
+    mut broken_props: ResMut<BrokenProps>,
+) {
+    let save = &pending.0;
+
+    commands.spawn((
+        Sprite::from_image(icons.samurai.clone()),
+        Transform::from_translation(save.player.translation),
+        Player {
+            health: save.player.health,
+            max_health: save.player.max_health,
+            xp: save.player.xp,
+            level: save.player.level,
+            next_level: save.player.next_level,
+            movement_speed_mod: save.player.movement_speed_mod,
+            attack_speed_mod: save.player.attack_speed_mod,
+            ..Player::new()
+        },
+    ));
+
+    for enemy in &save.enemies {
+        let Some(kind) = kinds.registry.find(&enemy.kind) else {
+            warn!("dropping saved enemy of unknown kind {:?}", enemy.kind);
+            continue;
+        };
+        let mut entity = commands.spawn(enemy_bundle(
+            &kinds,
+            &icons,
+            kind,
+            enemy.translation,
+            enemy.health,
+        ));
+        if kinds.get(kind).boss.is_some() {
+            entity.insert(Boss);
+        }
+        if !enemy.affixes.is_empty() {
+            entity.insert(Elite {
+                affixes: enemy.affixes.clone(),
+            });
+        }
+    }
+
+    for translation in &save.pickups {
+        commands.spawn(pickup_bundle(icons.health_potion.clone(), *translation));
+    }
+
+    spawner.cooldown = save.attack_spawner.cooldown.clone();
+    spawner.next_attack = save.attack_spawner.next_attack.clone();
+    spawner.n_attacks = save.attack_spawner.n_attacks;
+    spawner.attack_i = save.attack_spawner.attack_i;
+    stopwatch.clock.set_elapsed(save.elapsed);
+    broken_props.0.extend(save.broken_props.iter().copied());
+
+    commands.remove_resource::<PendingSave>();
+    info!("resumed saved run at {:.1}s", save.elapsed.as_secs_f32());
+}
+
+fn save_and_quit(
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    save_file: Res<SaveFile>,
+    player_query: Query<(&Player, &Transform)>,
+    enemy_query: Query<(&Enemy, &EnemyKind, &Transform, Option<&Elite>)>,
+    kinds: Res<EnemyKinds>,
+    pickup_query: Query<&Transform, With<Pickup>>,
+    spawner: Res<AttackSpawner>,
+    stopwatch: Res<GlobalStopwatch>,
+    broken_props: Res<BrokenProps>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if !settings
+        .key_bindings
+        .just_pressed(&input, Action::SaveAndQuit)
+    {
+        return;
+    }
+    let Ok((player, player_transform)) = player_query.get_single() else {
+        return;
+    };
+
+    let mut broken: Vec<IVec2> = broken_props.0.iter().copied().collect();
+    broken.sort_by_key(|tile| (tile.y, tile.x));
+
+    let save = SaveGame {
+        version: SAVE_VERSION,
+        elapsed: stopwatch.clock.elapsed(),
+        player: SavedPlayer {
+            translation: player_transform.translation,
+            health: player.health,
+            max_health: player.max_health,
+            xp: player.xp,
+            level: player.level,
+            next_level: player.next_level,
+            movement_speed_mod: player.movement_speed_mod,
+            attack_speed_mod: player.attack_speed_mod,
+        },
+        enemies: enemy_query
+            .iter()
+            .filter(|(enemy, _, _, _)| enemy.health > 0.)
+            .map(|(enemy, kind, transform, elite)| SavedEnemy {
+                translation: transform.translation,
+                health: enemy.health,
+                kind: kinds.get(*kind).name.clone(),
+                affixes: elite.map_or_else(Vec::new, |elite| elite.affixes.clone()),
+            })
+            .collect(),
+        pickups: pickup_query
+            .iter()
+            .map(|transform| transform.translation)
+            .collect(),
+        attack_spawner: SavedAttackSpawner {
+            cooldown: spawner.cooldown.clone(),
+            next_attack: spawner.next_attack.clone(),
+            n_attacks: spawner.n_attacks,
+            attack_i: spawner.attack_i,
+        },
+        broken_props: broken,
+    };
+
+    match save.save(&save_file.0) {
+        Ok(()) => {
+            info!("saved run to {}", save_file.0.display());
+            exit.send(AppExit::Success);
+        }
+        Err(err) => error!("failed to save run to {}: {}", save_file.0.display(), err),
+    }
+}
@@ -0,0 +1,297 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    audio::Volume,
+    prelude::*,
+    window::{MonitorSelection, PresentMode, PrimaryWindow, WindowMode},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assets::BackgroundMusic, launch::LaunchOptions, SCREEN_HEIGHT, SCREEN_WIDTH,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Pause,
+    Restart,
+    SaveAndQuit,
+    DebugXp,
+}
+
+impl Action {
+    pub const ALL: [Action; 8] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Pause,
+        Action::Restart,
+        Action::SaveAndQuit,
+        Action::DebugXp,
+    ];
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub move_up: Vec<KeyCode>,
+    pub move_down: Vec<KeyCode>,
+    pub move_left: Vec<KeyCode>,
+    pub move_right: Vec<KeyCode>,
+    pub pause: Vec<KeyCode>,
+    pub restart: Vec<KeyCode>,
+    pub save_and_quit: Vec<KeyCode>,
+    pub debug_xp: Vec<KeyCode>,
+}
+
+impl KeyBindings {
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        match action {
+            Action::MoveUp => &self.move_up,
+            Action::MoveDown => &self.move_down,
+            Action::MoveLeft => &self.move_left,
+            Action::MoveRight => &self.move_right,
+            Action::Pause => &self.pause,
+            Action::Restart => &self.restart,
+            Action::SaveAndQuit => &self.save_and_quit,
+            Action::DebugXp => &self.debug_xp,
+        }
+    }
+
+    pub fn pressed(&self, input: &ButtonInput<KeyCode>, action: Action) -> bool {
+        input.any_pressed(self.keys(action).iter().copied())
+    }
+
+    pub fn just_pressed(&self, input: &ButtonInput<KeyCode>, action: Action) -> bool {
+        input.any_just_pressed(self.keys(action).iter().copied())
+    }
+
+    pub fn label(&self, action: Action) -> String {
+        let Some(key) = self.keys(action).first() else {
+            return "unbound".to_string();
+        };
+        let name = format!("{key:?}");
+        name.strip_prefix("Key")
+            .or_else(|| name.strip_prefix("Digit"))
+            .unwrap_or(&name)
+            .to_string()
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            move_up: vec![KeyCode::KeyW, KeyCode::ArrowUp],
+            move_down: vec![KeyCode::KeyS, KeyCode::ArrowDown],
+            move_left: vec![KeyCode::KeyA, KeyCode::ArrowLeft],
+            move_right: vec![KeyCode::KeyD, KeyCode::ArrowRight],
+            pause: vec![KeyCode::Space],
+            restart: vec![KeyCode::KeyR],
+            save_and_quit: vec![KeyCode::KeyQ],
+            debug_xp: vec![KeyCode::NumpadAdd],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DisplayMode {
+    #[default]
+    Windowed,
+    BorderlessFullscreen,
+    Fullscreen,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowSettings {
+    pub width: f32,
+    pub height: f32,
+    pub mode: DisplayMode,
+    pub vsync: bool,
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        Self {
+            width: SCREEN_WIDTH,
+            height: SCREEN_HEIGHT,
+            mode: DisplayMode::Windowed,
+            vsync: true,
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    pub window: WindowSettings,
+    pub key_bindings: KeyBindings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.,
+            music_volume: 0.25,
+            sfx_volume: 0.5,
+            window: WindowSettings::default(),
+            key_bindings: KeyBindings::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn music(&self) -> Volume {
+        Volume::new(self.master_volume * self.music_volume)
+    }
+
+    pub fn sfx(&self) -> Volume {
+        Volume::new(self.master_volume * self.sfx_volume)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        ron::from_str(&fs::read_to_string(path)?)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let text = ron::ser::to_string_pretty(self, Default::default())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        fs::write(path, text)
+    }
+
+    // Never fails: a missing file is created with the defaults, and an
+    // unreadable one is left alone so it can be fixed by hand.
+    pub fn load_or_default(path: &Path) -> Self {
+        match Self::load(path) {
+            Ok(settings) => settings,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                warn!("no settings at {}, using defaults", path.display());
+                let settings = Self::default();
+                if let Err(err) = settings.save(path) {
+                    warn!("failed to write settings to {}: {}", path.display(), err);
+                }
+                settings
+            }
+            Err(err) => {
+                warn!(
+                    "failed to read settings {}, using defaults: {}",
+                    path.display(),
+                    err
+                );
+                Self::default()
+            }
+        }
+    }
+}
+
+pub fn default_settings_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_default()
+        .join("bevy_hell")
+        .join("settings.ron")
+}
+
+#[derive(Resource)]
+pub struct SettingsFile(pub PathBuf);
+
+// Without a path the defaults are used and nothing is written, which keeps
+// headless runs and tests away from the player's config.
+pub struct SettingsPlugin {
+    pub path: Option<PathBuf>,
+}
+
+impl Default for SettingsPlugin {
+    fn default() -> Self {
+        Self {
+            path: Some(default_settings_path()),
+        }
+    }
+}
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        match &self.path {
+            Some(path) => {
+                app.insert_resource(Settings::load_or_default(path))
+                    .insert_resource(SettingsFile(path.clone()));
+            }
+            None => {
+                app.init_resource::<Settings>();
+            }
+        }
+
+        app.add_systems(
+            Update,
+            (
+                (apply_window_settings, apply_music_volume)
+                    .run_if(resource_changed::<Settings>),
+                save_settings.run_if(
+                    resource_exists::<SettingsFile>
+                        .and(resource_changed::<Settings>)
+                        .and(not(resource_added::<Settings>)),
+                ),
+            ),
+        );
+    }
+}
+
+fn apply_window_settings(
+    settings: Res<Settings>,
+    options: Res<LaunchOptions>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let Ok(mut window) = window_query.get_single_mut() else {
+        return;
+    };
+
+    let (width, height) = options
+        .window
+        .unwrap_or((settings.window.width, settings.window.height));
+    window.resolution.set(width, height);
+    window.mode = match settings.window.mode {
+        DisplayMode::Windowed => WindowMode::Windowed,
+        DisplayMode::BorderlessFullscreen => {
+            WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+        }
+        DisplayMode::Fullscreen => WindowMode::Fullscreen(MonitorSelection::Current),
+    };
+    window.present_mode = if settings.window.vsync {
+        PresentMode::AutoVsync
+    } else {
+        PresentMode::AutoNoVsync
+    };
+}
+
+fn apply_music_volume(
+    settings: Res<Settings>,
+    music_query: Query<&AudioSink, With<BackgroundMusic>>,
+) {
+    for sink in music_query.iter() {
+        sink.set_volume(settings.music().get());
+    }
+}
+
+fn save_settings(settings: Res<Settings>, settings_file: Res<SettingsFile>) {
+    if let Err(err) = settings.save(&settings_file.0) {
+        error!(
+            "failed to save settings to {}: {}",
+            settings_file.0.display(),
+            err
+        );
+    }
+}
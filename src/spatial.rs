@@ -0,0 +1,180 @@
+use bevy::prelude::*;
+
+use crate::{
+    enemy::systems::enemy_movement, pickups::Pickup, props::Prop, Enemy, GameState,
+    MovementSet,
+};
+
+// Big enough that every collision check looks at no more than the 3x3 cells
+// around it.
+const GRID_CELL_SIZE: f32 = 64.;
+
+// Buckets points into square cells so neighbourhood queries only look at the
+// few cells a circle overlaps instead of every entity in the world. Cells are
+// hashed into a table sized to the number of points and the points are sorted
+// by bucket, so a rebuild is two passes over flat arrays with no allocation
+// once the buffers have grown.
+#[derive(Debug, Clone)]
+pub struct SpatialHash {
+    cell_size: f32,
+    // Bucket `b` holds `entries[starts[b]..starts[b + 1]]`.
+    starts: Vec<u32>,
+    entries: Vec<(Entity, Vec2)>,
+    unsorted: Vec<(Entity, Vec2, u32)>,
+}
+
+impl SpatialHash {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            starts: vec![0, 0],
+            entries: Vec::new(),
+            unsorted: Vec::new(),
+        }
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    fn cell_at(&self, position: Vec2) -> IVec2 {
+        (position / self.cell_size).floor().as_ivec2()
+    }
+
+    // The table always has a power of two buckets, plus the end marker.
+    fn bucket(&self, cell: IVec2) -> usize {
+        let hash = (cell.x as u32).wrapping_mul(0x9E37_79B1)
+            ^ (cell.y as u32).wrapping_mul(0x85EB_CA77);
+        hash as usize & (self.starts.len() - 2)
+    }
+
+    pub fn rebuild(&mut self, points: impl IntoIterator<Item = (Entity, Vec2)>) {
+        self.unsorted.clear();
+        self.unsorted.extend(
+            points
+                .into_iter()
+                .map(|(entity, position)| (entity, position, 0)),
+        );
+        self.starts.clear();
+        self.starts
+            .resize(self.unsorted.len().next_power_of_two() + 1, 0);
+
+        for i in 0..self.unsorted.len() {
+            let bucket = self.bucket(self.cell_at(self.unsorted[i].1));
+            self.unsorted[i].2 = bucket as u32;
+            self.starts[bucket + 1] += 1;
+        }
+        let buckets = self.starts.len() - 1;
+        for bucket in 0..buckets {
+            self.starts[bucket + 1] += self.starts[bucket];
+        }
+        // Fill each bucket from its end backwards, which leaves `starts`
+        // pointing at the beginnings again. Walking the points in reverse keeps
+        // them in their original order within a bucket.
+        self.entries.clear();
+        self.entries
+            .resize(self.unsorted.len(), (Entity::PLACEHOLDER, Vec2::ZERO));
+        for &(entity, position, bucket) in self.unsorted.iter().rev() {
+            let end = &mut self.starts[bucket as usize + 1];
+            *end -= 1;
+            self.entries[*end as usize] = (entity, position);
+        }
+        self.starts.rotate_left(1);
+        self.starts[buckets] = self.entries.len() as u32;
+    }
+
+    // Entities within `radius` of `position`, bucket by bucket and in the order
+    // they were given to `rebuild` within each bucket.
+    pub fn within(
+        &self,
+        position: Vec2,
+        radius: f32,
+    ) -> impl Iterator<Item = (Entity, Vec2)> + '_ {
+        let min = self.cell_at(position - radius);
+        let max = self.cell_at(position + radius);
+        let radius_squared = radius * radius;
+        let cells = move || {
+            (min.y..=max.y)
+                .flat_map(move |y| (min.x..=max.x).map(move |x| IVec2::new(x, y)))
+        };
+        cells()
+            .enumerate()
+            .map(move |(i, cell)| (i, self.bucket(cell)))
+            // Cells in range can share a bucket, which must only be read once.
+            .filter(move |&(i, bucket)| {
+                !cells()
+                    .take(i)
+                    .any(|earlier| self.bucket(earlier) == bucket)
+            })
+            .flat_map(move |(_, bucket)| {
+                &self.entries
+                    [self.starts[bucket] as usize..self.starts[bucket + 1] as usize]
+            })
+            .copied()
+            .filter(move |(_, other)| other.distance_squared(position) <= radius_squared)
+    }
+}
+
+// Where every enemy, prop and pickup stood once this tick's movement was done.
+// Collision systems ask it for candidates near a point and then run their own
+// exact checks on those, and enemies steer around it on the next tick.
+#[derive(Resource)]
+pub struct SpatialGrid {
+    hash: SpatialHash,
+}
+
+impl Default for SpatialGrid {
+    fn default() -> Self {
+        Self {
+            hash: SpatialHash::new(GRID_CELL_SIZE),
+        }
+    }
+}
+
+impl SpatialGrid {
+    pub fn rebuild(&mut self, entities: impl IntoIterator<Item = (Entity, Vec2)>) {
+        self.hash.rebuild(entities);
+    }
+
+    pub fn entities_within(
+        &self,
+        position: Vec2,
+        radius: f32,
+    ) -> impl Iterator<Item = Entity> + '_ {
+        self.within(position, radius).map(|(entity, _)| entity)
+    }
+
+    // Like `entities_within`, along with where each entity stood.
+    pub fn within(
+        &self,
+        position: Vec2,
+        radius: f32,
+    ) -> impl Iterator<Item = (Entity, Vec2)> + '_ {
+        self.hash.within(position, radius)
+    }
+}
+
+pub struct SpatialPlugin;
+
+impl Plugin for SpatialPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpatialGrid>().add_systems(
+            FixedUpdate,
+            rebuild_spatial_grid
+                .in_set(MovementSet)
+                .after(enemy_movement)
+                .run_if(in_state(GameState::Running)),
+        );
+    }
+}
+
+pub fn rebuild_spatial_grid(
+    mut grid: ResMut<SpatialGrid>,
+    query: Query<(Entity, &Transform), Or<(With<Enemy>, With<Prop>, With<Pickup>)>>,
+) {
+    grid.rebuild(
+        query
+            .iter()
+            .map(|(entity, transform)| (entity, transform.translation.truncate())),
+    );
+}
@@ -1,172 +1,304 @@
-use crate::{player::components::Player, GameState, GlobalStopwatch};
-
-use bevy::prelude::*;
-
-#[derive(Component)]
-pub struct PlayerHealth;
-
-#[derive(Component)]
-pub struct XPText;
-
-#[derive(Component)]
-pub struct LevelText;
-
-#[derive(Component)]
-pub struct TimeText;
-
-#[derive(Component)]
-pub struct GameOverText;
-
-pub struct UIPlugin;
-
-impl Plugin for UIPlugin {
-    fn build(&self, app: &mut App) {
-        app.add_systems(PostStartup, build_ui).add_systems(
-            Update,
-            (update_health, update_xp, update_level, update_time),
-        );
-        app.add_systems(OnEnter(GameState::GameOver), spawn_game_over_text);
-    }
-}
-
-fn build_ui(mut commands: Commands) {
-    commands
-        .spawn(Node {
-            width: Val::Percent(95.0),
-            height: Val::Percent(95.0),
-            flex_direction: FlexDirection::Column,
-            justify_content: JustifyContent::SpaceBetween,
-            align_items: AlignItems::Center,
-            align_self: AlignSelf::Center,
-            ..default()
-        })
-        .with_children(|parent| {
-            parent.spawn((Text::new(""), TimeText));
-            parent.spawn((
-                Node {
-                    left: Val::Percent(0.),
-                    top: Val::Percent(-5.),
-                    width: Val::Percent(110.0),
-                    height: Val::Percent(10.0),
-                    position_type: PositionType::Absolute,
-                    ..default()
-                },
-                BackgroundColor::from(Color::srgba(0., 0., 0., 0.5)),
-            ));
-            parent
-                .spawn(Node {
-                    width: Val::Percent(95.0),
-                    justify_content: JustifyContent::SpaceBetween,
-                    justify_items: JustifyItems::Stretch,
-                    align_self: AlignSelf::Center,
-                    ..default()
-                })
-                .with_children(|parent| {
-                    parent.spawn((Text::new(""), PlayerHealth));
-                    parent.spawn((Text::new(""), XPText));
-                    parent.spawn((Text::new(""), LevelText));
-                });
-            parent.spawn((
-                Node {
-                    width: Val::Percent(110.),
-                    height: Val::Percent(10.),
-                    left: Val::Percent(0.),
-                    bottom: Val::Percent(-5.),
-                    position_type: PositionType::Absolute,
-                    ..default()
-                },
-                BackgroundColor::from(Color::srgba(0., 0., 0., 0.5)),
-            ));
-        });
-}
-
-fn spawn_game_over_text(mut commands: Commands) {
-    commands
-        .spawn((
-            Node {
-                width: Val::Percent(100.),
-                height: Val::Percent(100.),
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
-                flex_direction: FlexDirection::Column,
-                ..default()
-            },
-            BackgroundColor::from(Color::srgba(0., 0., 0., 0.5)),
-            GameOverText,
-        ))
-        .with_children(|parent| {
-            parent.spawn((
-                Text::new("GAME OVER"),
-                TextFont {
-                    font_size: 100.,
-                    ..default()
-                },
-            ));
-            parent.spawn((
-                Text::new("Press 'R' to restart"),
-                TextFont {
-                    font_size: 25.,
-                    ..default()
-                },
-            ));
-        });
-}
-
-fn update_health(
-    player_query: Query<&Player>,
-    mut health_query: Query<&mut Text, With<PlayerHealth>>,
-) {
-    let Ok(player) = player_query.get_single() else {
-        return;
-    };
-
-    let Ok(mut health_text) = health_query.get_single_mut() else {
-        return;
-    };
-
-    let health = std::cmp::max(player.health as i32, 0);
-    let max_health = player.max_health;
-    **health_text = format!("HP: {}/{}", health, max_health);
-}
-
-fn update_xp(player_query: Query<&Player>, mut xp_query: Query<&mut Text, With<XPText>>) {
-    let Ok(player) = player_query.get_single() else {
-        return;
-    };
-
-    let Ok(mut xp_text) = xp_query.get_single_mut() else {
-        return;
-    };
-
-    **xp_text = format!("XP: {}/{}", player.xp, player.next_level);
-}
-
-fn update_level(
-    player_query: Query<&Player>,
-    mut level_query: Query<&mut Text, With<LevelText>>,
-) {
-    let Ok(player) = player_query.get_single() else {
-        return;
-    };
-
-    let Ok(mut level_text) = level_query.get_single_mut() else {
-        return;
-    };
-
-    **level_text = format!("Level: {}", player.level);
-}
-
-fn update_time(
-    time: Res<GlobalStopwatch>,
-    mut time_query: Query<&mut Text, With<TimeText>>,
-) {
-    let Ok(mut time_text) = time_query.get_single_mut() else {
-        return;
-    };
-
-    let total_seconds = time.clock.elapsed_secs_f64().round() as u32;
-    let minutes = total_seconds / 60;
-    let seconds = total_seconds % 60;
-
-    **time_text = format!("Time: {:02}:{:02}", minutes, seconds);
-}
+use crate::{
+    enemy::{
+        components::{Boss, Enemy},
+        kinds::{EnemyKind, EnemyKinds},
+    },
+    player::components::Player,
+    settings::{Action, Settings},
+    GameState, GlobalStopwatch,
+};
+
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct PlayerHealth;
+
+#[derive(Component)]
+pub struct XPText;
+
+#[derive(Component)]
+pub struct LevelText;
+
+#[derive(Component)]
+pub struct TimeText;
+
+// Shown while a boss is alive.
+#[derive(Component)]
+pub struct BossBar;
+
+#[derive(Component)]
+pub struct BossName;
+
+#[derive(Component)]
+pub struct BossHealthFill;
+
+#[derive(Component)]
+pub struct GameOverText;
+
+#[derive(Component)]
+pub struct PausedText;
+
+pub struct UIPlugin;
+
+impl Plugin for UIPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PostStartup, build_ui).add_systems(
+            Update,
+            (
+                update_health,
+                update_xp,
+                update_level,
+                update_time,
+                update_boss_bar,
+            ),
+        );
+        app.add_systems(OnEnter(GameState::GameOver), spawn_game_over_text);
+        app.add_systems(OnEnter(GameState::Paused), spawn_paused_text);
+    }
+}
+
+fn build_ui(mut commands: Commands) {
+    commands
+        .spawn(Node {
+            width: Val::Percent(95.0),
+            height: Val::Percent(95.0),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::SpaceBetween,
+            align_items: AlignItems::Center,
+            align_self: AlignSelf::Center,
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((Text::new(""), TimeText));
+            parent
+                .spawn((
+                    Node {
+                        top: Val::Percent(6.),
+                        width: Val::Percent(50.),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        position_type: PositionType::Absolute,
+                        display: Display::None,
+                        ..default()
+                    },
+                    BossBar,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((Text::new(""), BossName));
+                    parent
+                        .spawn((
+                            Node {
+                                width: Val::Percent(100.),
+                                height: Val::Px(12.),
+                                ..default()
+                            },
+                            BackgroundColor::from(Color::srgba(0., 0., 0., 0.6)),
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn((
+                                Node {
+                                    width: Val::Percent(100.),
+                                    height: Val::Percent(100.),
+                                    ..default()
+                                },
+                                BackgroundColor::from(Color::srgb(0.8, 0.1, 0.1)),
+                                BossHealthFill,
+                            ));
+                        });
+                });
+            parent.spawn((
+                Node {
+                    left: Val::Percent(0.),
+                    top: Val::Percent(-5.),
+                    width: Val::Percent(110.0),
+                    height: Val::Percent(10.0),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                BackgroundColor::from(Color::srgba(0., 0., 0., 0.5)),
+            ));
+            parent
+                .spawn(Node {
+                    width: Val::Percent(95.0),
+                    justify_content: JustifyContent::SpaceBetween,
+                    justify_items: JustifyItems::Stretch,
+                    align_self: AlignSelf::Center,
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((Text::new(""), PlayerHealth));
+                    parent.spawn((Text::new(""), XPText));
+                    parent.spawn((Text::new(""), LevelText));
+                });
+            parent.spawn((
+                Node {
+                    width: Val::Percent(110.),
+                    height: Val::Percent(10.),
+                    left: Val::Percent(0.),
+                    bottom: Val::Percent(-5.),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                BackgroundColor::from(Color::srgba(0., 0., 0., 0.5)),
+            ));
+        });
+}
+
+fn spawn_game_over_text(mut commands: Commands, settings: Res<Settings>) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            BackgroundColor::from(Color::srgba(0., 0., 0., 0.5)),
+            GameOverText,
+            StateScoped(GameState::GameOver),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("GAME OVER"),
+                TextFont {
+                    font_size: 100.,
+                    ..default()
+                },
+            ));
+            parent.spawn((
+                Text::new(format!(
+                    "Press '{}' to restart",
+                    settings.key_bindings.label(Action::Restart)
+                )),
+                TextFont {
+                    font_size: 25.,
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn spawn_paused_text(mut commands: Commands, settings: Res<Settings>) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            BackgroundColor::from(Color::srgba(0., 0., 0., 0.5)),
+            PausedText,
+            StateScoped(GameState::Paused),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("PAUSED"),
+                TextFont {
+                    font_size: 100.,
+                    ..default()
+                },
+            ));
+            parent.spawn((
+                Text::new(format!(
+                    "Press '{}' to resume or '{}' to save and quit",
+                    settings.key_bindings.label(Action::Pause),
+                    settings.key_bindings.label(Action::SaveAndQuit)
+                )),
+                TextFont {
+                    font_size: 25.,
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn update_health(
+    player_query: Query<&Player>,
+    mut health_query: Query<&mut Text, With<PlayerHealth>>,
+) {
+    let Ok(player) = player_query.get_single() else {
+        return;
+    };
+
+    let Ok(mut health_text) = health_query.get_single_mut() else {
+        return;
+    };
+
+    let health = std::cmp::max(player.health as i32, 0);
+    let max_health = player.max_health;
+    **health_text = format!("HP: {}/{}", health, max_health);
+}
+
+fn update_xp(player_query: Query<&Player>, mut xp_query: Query<&mut Text, With<XPText>>) {
+    let Ok(player) = player_query.get_single() else {
+        return;
+    };
+
+    let Ok(mut xp_text) = xp_query.get_single_mut() else {
+        return;
+    };
+
+    **xp_text = format!("XP: {}/{}", player.xp, player.next_level);
+}
+
+fn update_level(
+    player_query: Query<&Player>,
+    mut level_query: Query<&mut Text, With<LevelText>>,
+) {
+    let Ok(player) = player_query.get_single() else {
+        return;
+    };
+
+    let Ok(mut level_text) = level_query.get_single_mut() else {
+        return;
+    };
+
+    **level_text = format!("Level: {}", player.level);
+}
+
+fn update_time(
+    time: Res<GlobalStopwatch>,
+    mut time_query: Query<&mut Text, With<TimeText>>,
+) {
+    let Ok(mut time_text) = time_query.get_single_mut() else {
+        return;
+    };
+
+    let total_seconds = time.clock.elapsed_secs_f64().round() as u32;
+    let minutes = total_seconds / 60;
+    let seconds = total_seconds % 60;
+
+    **time_text = format!("Time: {:02}:{:02}", minutes, seconds);
+}
+
+fn update_boss_bar(
+    boss_query: Query<(&Enemy, &EnemyKind), With<Boss>>,
+    kinds: Res<EnemyKinds>,
+    mut bar_query: Query<&mut Node, (With<BossBar>, Without<BossHealthFill>)>,
+    mut name_query: Query<&mut Text, With<BossName>>,
+    mut fill_query: Query<&mut Node, (With<BossHealthFill>, Without<BossBar>)>,
+) {
+    let Ok(mut bar) = bar_query.get_single_mut() else {
+        return;
+    };
+
+    let Some((enemy, kind)) = boss_query.iter().next() else {
+        bar.display = Display::None;
+        return;
+    };
+    bar.display = Display::Flex;
+
+    let enemy_type = kinds.get(*kind);
+    if let Ok(mut name) = name_query.get_single_mut() {
+        **name = enemy_type.name.to_uppercase();
+    }
+    if let Ok(mut fill) = fill_query.get_single_mut() {
+        let fraction = (enemy.health / enemy_type.health).clamp(0., 1.);
+        fill.width = Val::Percent(fraction * 100.);
+    }
+}
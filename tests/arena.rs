@@ -0,0 +1,106 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_hell::{
+    enemy::components::ENEMY_RADIUS,
+    launch::LaunchOptions,
+    map::{
+        definition::{MapDefinition, SpawnZone},
+        walls::Wall,
+        ActiveMap,
+    },
+    player::components::PLAYER_RADIUS,
+    rng::{GameRng, RngStream},
+    Player,
+};
+use common::*;
+
+const BOUNDS: Rect = Rect {
+    min: Vec2::new(-500., -500.),
+    max: Vec2::new(500., 500.),
+};
+
+fn arena() -> MapDefinition {
+    MapDefinition {
+        scatter_trees: false,
+        spawn_zones: vec![SpawnZone::Ring {
+            inner: 700.,
+            outer: 1200.,
+        }],
+        bounds: Some(BOUNDS),
+        ..default()
+    }
+}
+
+fn arena_app() -> App {
+    let mut app = test_app();
+    app.world_mut().resource_mut::<ActiveMap>().definition = arena();
+    app.update();
+    app
+}
+
+fn player_position(app: &mut App) -> Vec2 {
+    app.world_mut()
+        .query_filtered::<&Transform, With<Player>>()
+        .single(app.world())
+        .translation
+        .truncate()
+}
+
+#[test]
+fn arena_map_file_is_walled_in() {
+    let mut app = test_app_with(LaunchOptions {
+        map: Some("maps/arena.map.ron".into()),
+        ..default()
+    });
+
+    assert!(app
+        .world()
+        .resource::<ActiveMap>()
+        .definition
+        .bounds
+        .is_some());
+    assert_eq!(count::<Wall>(&mut app), 4);
+}
+
+#[test]
+fn player_cannot_leave_the_arena() {
+    let mut app = arena_app();
+    teleport_player(&mut app, Vec2::new(450., 0.));
+
+    press_key(&mut app, KeyCode::KeyD);
+    advance(&mut app, 120);
+
+    assert_eq!(player_position(&mut app).x, BOUNDS.max.x - PLAYER_RADIUS);
+}
+
+#[test]
+fn enemies_are_kept_inside_the_arena() {
+    let mut app = arena_app();
+    teleport_player(&mut app, Vec2::new(-450., 0.));
+    let enemy = spawn_enemy(&mut app, Vec2::new(900., 900.), 1000.);
+
+    advance(&mut app, 2);
+
+    let position = app
+        .world()
+        .get::<Transform>(enemy)
+        .unwrap()
+        .translation
+        .truncate();
+    assert!(BOUNDS.inflate(-ENEMY_RADIUS).contains(position));
+}
+
+#[test]
+fn spawns_near_a_wall_are_pushed_inside_and_away_from_the_player() {
+    let map = arena();
+    let player = Vec2::new(480., 0.);
+    let mut rng = GameRng::new(SEED);
+    let rng = rng.stream(RngStream::EnemySpawn);
+
+    for _ in 0..500 {
+        let point = map.spawn_point(rng, player);
+        assert!(BOUNDS.contains(point));
+        assert!(point.distance(player) >= 400., "{point} is too close");
+    }
+}
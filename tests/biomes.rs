@@ -0,0 +1,97 @@
+mod common;
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy_hell::{
+    enemy::kinds::{EnemyKind, EnemyKinds},
+    map::{biomes::Biome, chunks::Ground, definition::MapDefinition},
+};
+use common::*;
+
+fn sample_tiles() -> impl Iterator<Item = IVec2> {
+    (-200..200).flat_map(|y| (-200..200).map(move |x| IVec2::new(x, y) * 2))
+}
+
+fn tiles_by_biome(map: &MapDefinition) -> HashMap<Biome, Vec<IVec2>> {
+    let mut biomes: HashMap<Biome, Vec<IVec2>> = HashMap::new();
+    for tile in sample_tiles() {
+        biomes
+            .entry(map.biome_at(SEED, tile))
+            .or_default()
+            .push(tile);
+    }
+    biomes
+}
+
+fn share(tiles: &[IVec2], test: impl Fn(IVec2) -> bool) -> f32 {
+    tiles.iter().filter(|tile| test(**tile)).count() as f32 / tiles.len() as f32
+}
+
+#[test]
+fn biomes_form_large_seeded_regions() {
+    let map = MapDefinition::default();
+    let biomes = tiles_by_biome(&map);
+    assert_eq!(biomes.len(), 3);
+
+    let changes = sample_tiles()
+        .filter(|tile| map.biome_at(SEED, *tile) != map.biome_at(SEED, *tile + IVec2::X))
+        .count();
+    assert!(changes * 20 < sample_tiles().count());
+
+    assert!(sample_tiles()
+        .any(|tile| map.biome_at(SEED, tile) != map.biome_at(SEED + 1, tile)));
+}
+
+#[test]
+fn biomes_set_ground_and_tree_density() {
+    let map = MapDefinition::default();
+    let biomes = tiles_by_biome(&map);
+    let dirt = |biome| {
+        share(&biomes[&biome], |tile| {
+            map.ground_at(SEED, tile) == Ground::Dirt
+        })
+    };
+    let trees = |biome| share(&biomes[&biome], |tile| map.tree_at(SEED, tile));
+
+    assert!(dirt(Biome::Wastes) > dirt(Biome::Meadow));
+    assert!(dirt(Biome::Meadow) > dirt(Biome::Forest));
+    assert!(trees(Biome::Forest) > trees(Biome::Meadow));
+    assert!(trees(Biome::Meadow) > trees(Biome::Wastes));
+}
+
+#[test]
+fn biomes_pick_their_own_enemies() {
+    let app = test_app();
+    let (runner, brute) = (kind(&app, "runner"), kind(&app, "brute"));
+    let registry = &app.world().resource::<EnemyKinds>().registry;
+    let kinds = |biome: Biome| -> Vec<EnemyKind> {
+        (0..100)
+            .map(|i| registry.pick(biome, i as f32 / 100.))
+            .collect()
+    };
+
+    let forest = kinds(Biome::Forest);
+    assert!(forest.contains(&brute));
+    assert!(!forest.contains(&runner));
+
+    let wastes = kinds(Biome::Wastes);
+    assert!(wastes.contains(&runner));
+    assert!(!wastes.contains(&brute));
+}
+
+#[test]
+fn runners_outpace_brutes() {
+    let mut app = test_app();
+    let start = Vec2::new(400., 0.);
+    let runner = spawn_kind(&mut app, "runner", start, 100.);
+    let brute = spawn_kind(&mut app, "brute", -start, 100.);
+
+    advance(&mut app, 60);
+
+    let travelled = |app: &App, entity: Entity, from: Vec2| {
+        let position = app.world().get::<Transform>(entity).unwrap().translation;
+        position.truncate().distance(from)
+    };
+    assert!(travelled(&app, runner, start) > 2. * travelled(&app, brute, -start));
+}
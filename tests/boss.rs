@@ -0,0 +1,177 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_hell::{
+    bullet::Faction,
+    enemy::{
+        boss::BossEncounter,
+        components::Boss,
+        kinds::{AttackPattern, EnemyKinds},
+        waves::{Wave, Waves},
+    },
+    launch::LaunchOptions,
+    map::biomes::Biome,
+    pickups::Pickup,
+    Attack, Enemy, GlobalStopwatch,
+};
+use common::*;
+
+const KING: &str = "blob king";
+
+fn god_app() -> App {
+    test_app_with(LaunchOptions {
+        god: true,
+        ..default()
+    })
+}
+
+fn spawn_boss(app: &mut App, position: Vec2, health: f32) -> Entity {
+    let boss = spawn_kind(app, KING, position, health);
+    app.world_mut().entity_mut(boss).insert(Boss);
+    boss
+}
+
+fn regular_enemies(app: &mut App) -> usize {
+    app.world_mut()
+        .query_filtered::<(), (With<Enemy>, Without<Boss>)>()
+        .iter(app.world())
+        .count()
+}
+
+#[test]
+fn bosses_spawn_once_at_their_mark() {
+    let mut app = god_app();
+    app.world_mut().resource_mut::<Waves>().schedule.bosses = vec![BossEncounter {
+        at: 1.,
+        kind: KING.into(),
+    }];
+
+    advance(&mut app, 50);
+    assert_eq!(count::<Boss>(&mut app), 0);
+
+    advance(&mut app, 20);
+    assert_eq!(count::<Boss>(&mut app), 1);
+
+    advance(&mut app, 120);
+    assert_eq!(count::<Boss>(&mut app), 1);
+}
+
+#[test]
+fn seeded_runs_through_boss_marks_repeat_exactly() {
+    let run = || {
+        let mut app = god_app();
+        // Close enough together that some land on ticks the waves spawn on.
+        app.world_mut().resource_mut::<Waves>().schedule.bosses = (0..20)
+            .map(|i| BossEncounter {
+                at: 300. + i as f32 * 0.05,
+                kind: KING.into(),
+            })
+            .collect();
+        app.world_mut()
+            .resource_mut::<GlobalStopwatch>()
+            .clock
+            .set_elapsed(Duration::from_secs_f32(299.5));
+        advance(&mut app, 120);
+
+        assert_eq!(count::<Boss>(&mut app), 20);
+        let mut enemies: Vec<(Vec3, f32)> = app
+            .world_mut()
+            .query::<(&Transform, &Enemy)>()
+            .iter(app.world())
+            .map(|(transform, enemy)| (transform.translation, enemy.health))
+            .collect();
+        enemies.sort_by(|a, b| a.0.to_array().partial_cmp(&b.0.to_array()).unwrap());
+        enemies
+    };
+
+    assert_eq!(run(), run());
+}
+
+#[test]
+fn waves_thin_out_while_a_boss_is_alive() {
+    let mut spawned = Vec::new();
+    for with_boss in [false, true] {
+        let mut app = god_app();
+        app.world_mut()
+            .resource_mut::<GlobalStopwatch>()
+            .clock
+            .set_elapsed(Duration::from_secs(240));
+        if with_boss {
+            spawn_boss(&mut app, Vec2::new(3000., 0.), 1e6);
+        }
+        advance(&mut app, 120);
+        spawned.push(regular_enemies(&mut app));
+    }
+
+    assert!(spawned[1] * 3 < spawned[0], "{spawned:?}");
+}
+
+#[test]
+fn phases_follow_the_boss_health() {
+    let app = test_app();
+    let kinds = app.world().resource::<EnemyKinds>();
+    let king = kinds.get(kinds.registry.find(KING).unwrap());
+    let pattern = |fraction: f32| &king.phase(king.health * fraction).unwrap().pattern;
+
+    assert!(matches!(pattern(1.), AttackPattern::Fan { .. }));
+    assert!(matches!(pattern(0.5), AttackPattern::Ring { .. }));
+    assert!(matches!(pattern(0.1), AttackPattern::Spiral { .. }));
+}
+
+#[test]
+fn bosses_never_spawn_from_weights() {
+    let app = test_app();
+    let mut registry = app.world().resource::<EnemyKinds>().registry.clone();
+    let king = registry.find(KING).unwrap();
+    for enemy in &mut registry.kinds {
+        enemy.spawn_weights = [(Biome::Meadow, 1)].into();
+    }
+    let wave = Wave {
+        kinds: vec![(KING.into(), 1), ("blob".into(), 1)],
+        ..app.world().resource::<Waves>().schedule.waves[0].clone()
+    };
+
+    let table = wave.kind_table(&registry).unwrap();
+    assert!(table.iter().all(|(kind, _)| *kind != king));
+    assert!((0..100).all(|i| registry.pick(Biome::Meadow, i as f32 / 100.) != king));
+}
+
+#[test]
+fn wounded_bosses_fire_a_ring() {
+    let mut app = god_app();
+    let king = kind(&app, KING);
+    let health = app.world().resource::<EnemyKinds>().get(king).health;
+    spawn_boss(&mut app, Vec2::new(300., 0.), health * 0.5);
+
+    app.update();
+
+    assert_eq!(count_bullets(&mut app, Faction::Enemy), 16);
+}
+
+#[test]
+fn bosses_always_drop_rewards() {
+    let mut app = god_app();
+    spawn_boss(&mut app, Vec2::new(300., 0.), 0.);
+
+    app.update();
+
+    assert_eq!(count::<Boss>(&mut app), 0);
+    assert_eq!(count::<Pickup>(&mut app), 5);
+}
+
+#[test]
+fn the_whole_boss_body_touches_and_can_be_hit() {
+    let mut app = test_app();
+    let boss = spawn_boss(&mut app, Vec2::new(50., 0.), 1e6);
+    app.world_mut().spawn((
+        Attack::new(),
+        Transform::from_translation(Vec3::new(-20., 0., 0.)),
+    ));
+
+    advance(&mut app, 10);
+
+    assert!(player(&mut app).health < 100.);
+    assert!(app.world().get::<Enemy>(boss).unwrap().health < 1e6);
+}
@@ -0,0 +1,119 @@
+#![allow(dead_code)]
+
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput, NativeKey},
+        ButtonState,
+    },
+    prelude::*,
+};
+use bevy_hell::{
+    bullet::{Bullet, Faction},
+    enemy::kinds::{EnemyKind, EnemyKinds},
+    headless::HeadlessPlugin,
+    interpolation::Interpolated,
+    launch::LaunchOptions,
+    rng::GameRng,
+    BevyHellPlugins, Enemy, GameState, Player,
+};
+
+pub const SEED: u64 = 0;
+
+// Builds the gameplay plugins without a window and runs frames until the map
+// has loaded and `GameState::Running` has spawned the player at the origin.
+// That last frame plays the run's first fixed tick, and every later
+// `app.update()` advances the simulation by exactly one more.
+pub fn test_app() -> App {
+    test_app_with(LaunchOptions::default())
+}
+
+pub fn test_app_with(options: LaunchOptions) -> App {
+    let mut app = App::new();
+    app.insert_resource(options)
+        .add_plugins((HeadlessPlugin, BevyHellPlugins::headless()))
+        .insert_resource(GameRng::new(SEED));
+    while state(&app) == GameState::Loading {
+        app.update();
+    }
+    app
+}
+
+pub fn advance(app: &mut App, ticks: u32) {
+    for _ in 0..ticks {
+        app.update();
+    }
+}
+
+pub fn state(app: &App) -> GameState {
+    *app.world().resource::<State<GameState>>().get()
+}
+
+pub fn player(app: &mut App) -> Mut<'_, Player> {
+    app.world_mut()
+        .query::<&mut Player>()
+        .single_mut(app.world_mut())
+}
+
+// Moves the player outright, skipping interpolation towards the new spot.
+pub fn teleport_player(app: &mut App, position: Vec2) {
+    let (mut transform, mut interpolated) = app
+        .world_mut()
+        .query_filtered::<(&mut Transform, &mut Interpolated), With<Player>>()
+        .single_mut(app.world_mut());
+    transform.translation = position.extend(1.);
+    interpolated.previous = transform.translation;
+    interpolated.current = transform.translation;
+}
+
+pub fn count<C: Component>(app: &mut App) -> usize {
+    app.world_mut()
+        .query_filtered::<(), With<C>>()
+        .iter(app.world())
+        .count()
+}
+
+pub fn spawn_enemy(app: &mut App, position: Vec2, health: f32) -> Entity {
+    app.world_mut()
+        .spawn((
+            Sprite::default(),
+            Transform::from_translation(position.extend(1.)),
+            Enemy {
+                health,
+                last_damage: 0.,
+            },
+        ))
+        .id()
+}
+
+pub fn kind(app: &App, name: &str) -> EnemyKind {
+    let kinds = app.world().resource::<EnemyKinds>();
+    kinds.registry.find(name).expect("a kind in the registry")
+}
+
+// An enemy of the registry's kind called `name`.
+pub fn spawn_kind(app: &mut App, name: &str, position: Vec2, health: f32) -> Entity {
+    let kind = kind(app, name);
+    let enemy = spawn_enemy(app, position, health);
+    app.world_mut().entity_mut(enemy).insert(kind);
+    enemy
+}
+
+pub fn count_bullets(app: &mut App, faction: Faction) -> usize {
+    app.world_mut()
+        .query::<&Bullet>()
+        .iter(app.world())
+        .filter(|bullet| bullet.faction == faction)
+        .count()
+}
+
+// Goes through the keyboard event path, since `ButtonInput` is cleared at the
+// start of every frame before gameplay systems get to read it.
+pub fn press_key(app: &mut App, key_code: KeyCode) {
+    app.world_mut().send_event(KeyboardInput {
+        key_code,
+        logical_key: Key::Unidentified(NativeKey::Unidentified),
+        state: ButtonState::Pressed,
+        repeat: false,
+        window: Entity::PLACEHOLDER,
+    });
+}
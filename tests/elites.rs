@@ -0,0 +1,147 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_hell::{
+    enemy::{
+        elites::{Affix, Elite},
+        kinds::EnemyKinds,
+    },
+    launch::LaunchOptions,
+    pickups::Pickup,
+    rng::{GameRng, RngStream},
+    Enemy,
+};
+use common::*;
+
+fn spawn_elite(app: &mut App, position: Vec2, health: f32, affixes: &[Affix]) -> Entity {
+    let enemy = spawn_enemy(app, position, health);
+    app.world_mut().entity_mut(enemy).insert(Elite {
+        affixes: affixes.to_vec(),
+    });
+    enemy
+}
+
+fn enemy_scales(app: &mut App) -> Vec<f32> {
+    app.world_mut()
+        .query_filtered::<&Transform, With<Enemy>>()
+        .iter(app.world())
+        .map(|transform| transform.scale.x)
+        .collect()
+}
+
+#[test]
+fn elites_get_more_common_and_varied_over_a_run() {
+    let mut rng = GameRng::new(SEED);
+    let rng = rng.stream(RngStream::Elites);
+    let mut roll = |elapsed: f64| -> Vec<Elite> {
+        (0..2000)
+            .filter_map(|_| Elite::roll(elapsed, rng))
+            .collect()
+    };
+
+    assert!(roll(0.).is_empty());
+
+    let early = roll(90.);
+    let late = roll(1800.);
+    assert!(early.len() < late.len());
+    assert!(early.iter().all(|elite| elite.affixes.len() == 1
+        && elite.affixes.iter().all(|affix| affix.unlocked_at() <= 90.)));
+    assert!(late.iter().all(|elite| elite.affixes.len() == 3));
+    for affix in Affix::ALL {
+        assert!(late.iter().any(|elite| elite.has(affix)));
+    }
+}
+
+#[test]
+fn elites_stand_out() {
+    let mut app = test_app();
+    let elite = spawn_elite(&mut app, Vec2::new(300., 0.), 10., &[Affix::Vampiric]);
+
+    let transform = app.world().get::<Transform>(elite).unwrap();
+    assert_eq!(transform.scale, Vec3::splat(1.25));
+    let sprite = app.world().get::<Sprite>(elite).unwrap();
+    assert_ne!(sprite.color, Color::WHITE);
+}
+
+#[test]
+fn elites_are_worth_more() {
+    let mut app = test_app();
+    let xp = app.world().resource::<EnemyKinds>().registry.kinds[0].xp;
+    spawn_elite(
+        &mut app,
+        Vec2::new(300., 0.),
+        0.,
+        &[
+            Affix::Fast,
+            Affix::Armored,
+            Affix::Regenerating,
+            Affix::Vampiric,
+        ],
+    );
+
+    advance(&mut app, 1);
+
+    assert_eq!(player(&mut app).xp, xp * 5);
+    // A quarter chance to drop a potion for each affix.
+    assert_eq!(count::<Pickup>(&mut app), 1);
+}
+
+#[test]
+fn splitting_elites_leave_two_smaller_enemies() {
+    let mut app = test_app();
+    spawn_elite(&mut app, Vec2::new(300., 0.), 0., &[Affix::Splitting]);
+
+    advance(&mut app, 1);
+
+    let scales = enemy_scales(&mut app);
+    assert_eq!(scales.len(), 2);
+    assert!(scales.iter().all(|scale| *scale < 1.));
+}
+
+#[test]
+fn explosive_elites_hurt_the_player_when_they_die_close_by() {
+    let mut app = test_app();
+    spawn_elite(&mut app, Vec2::new(500., 0.), 0., &[Affix::Explosive]);
+    advance(&mut app, 1);
+    assert_eq!(player(&mut app).health, 100.);
+
+    spawn_elite(&mut app, Vec2::new(40., 0.), 0., &[Affix::Explosive]);
+    advance(&mut app, 1);
+    assert!(player(&mut app).health < 100.);
+}
+
+#[test]
+fn explosions_spare_the_player_in_god_mode() {
+    let mut app = test_app_with(LaunchOptions {
+        god: true,
+        ..default()
+    });
+    spawn_elite(&mut app, Vec2::new(40., 0.), 0., &[Affix::Explosive]);
+
+    advance(&mut app, 1);
+
+    assert_eq!(count::<Enemy>(&mut app), 0);
+    assert_eq!(player(&mut app).health, 100.);
+}
+
+#[test]
+fn regenerating_elites_heal_while_left_alone() {
+    let mut app = test_app();
+    let elite = spawn_elite(&mut app, Vec2::new(600., 0.), 2., &[Affix::Regenerating]);
+
+    advance(&mut app, 120);
+
+    let health = app.world().get::<Enemy>(elite).unwrap().health;
+    assert!(health > 2. && health <= 10., "{health}");
+}
+
+#[test]
+fn vampiric_elites_feed_on_the_player() {
+    let mut app = test_app();
+    let elite = spawn_elite(&mut app, Vec2::new(10., 0.), 5., &[Affix::Vampiric]);
+
+    advance(&mut app, 10);
+
+    assert!(player(&mut app).health < 100.);
+    assert!(app.world().get::<Enemy>(elite).unwrap().health > 5.);
+}
@@ -0,0 +1,171 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_hell::{
+    enemy::kinds::{EnemyKind, EnemyKinds, EnemyRegistry},
+    map::biomes::Biome,
+    Enemy,
+};
+use common::*;
+
+#[test]
+fn registry_is_loaded_from_its_file() {
+    let app = test_app();
+    let registry = &app.world().resource::<EnemyKinds>().registry;
+    let names: Vec<&str> = registry
+        .kinds
+        .iter()
+        .map(|enemy| enemy.name.as_str())
+        .collect();
+
+    assert_eq!(names, ["blob", "runner", "brute", "spitter", "blob king"]);
+    // The file's blob is the same as the built-in one, apart from where it
+    // spawns.
+    let blob = registry.get(EnemyKind(0));
+    let builtin = &EnemyRegistry::default().kinds[0];
+    assert_eq!(
+        (blob.health, blob.speed, blob.contact_damage, blob.xp),
+        (
+            builtin.health,
+            builtin.speed,
+            builtin.contact_damage,
+            builtin.xp
+        )
+    );
+}
+
+#[test]
+fn kills_award_the_xp_of_the_kind() {
+    let mut app = test_app();
+    let brute = kind(&app, "brute");
+    let xp = app.world().resource::<EnemyKinds>().get(brute).xp;
+    spawn_kind(&mut app, "brute", Vec2::new(300., 0.), 0.);
+
+    advance(&mut app, 1);
+
+    assert_eq!(player(&mut app).xp, xp);
+}
+
+#[test]
+fn contact_damage_comes_from_the_kind() {
+    let mut app = test_app();
+    let brute = kind(&app, "brute");
+    let damage = app
+        .world()
+        .resource::<EnemyKinds>()
+        .get(brute)
+        .contact_damage;
+    spawn_kind(&mut app, "brute", Vec2::new(10., 0.), 1000.);
+
+    advance(&mut app, 30);
+
+    let lost = 100. - player(&mut app).health;
+    assert!(lost > 0.);
+    assert!((lost / damage - (lost / damage).round()).abs() < 1e-3);
+}
+
+#[test]
+fn contact_damage_lands_every_tenth_of_a_second() {
+    let mut hits = Vec::new();
+    // Enemies far from the player do not speed up the hits of those on it.
+    for crowd in [0, 40] {
+        let mut app = test_app();
+        spawn_kind(&mut app, "blob", Vec2::new(10., 0.), 1000.);
+        for i in 0..crowd {
+            let position = Vec2::from_angle(i as f32) * 3000.;
+            spawn_kind(&mut app, "blob", position, 1000.);
+        }
+        advance(&mut app, 60);
+
+        let kinds = app.world().resource::<EnemyKinds>();
+        let damage = kinds.get(kind(&app, "blob")).contact_damage;
+        hits.push(((100. - player(&mut app).health) / damage).round());
+    }
+
+    assert_eq!(hits[0], hits[1]);
+    assert!((9. ..=11.).contains(&hits[0]), "{hits:?}");
+}
+
+#[test]
+fn new_kinds_spawn_without_code_changes() {
+    let mut app = test_app();
+    let handle = app.world().resource::<EnemyKinds>().handle.clone();
+    let mut registries = app.world_mut().resource_mut::<Assets<EnemyRegistry>>();
+    let registry = registries.get_mut(&handle).unwrap();
+    let mut ghost = registry.kinds[0].clone();
+    ghost.name = "ghost".into();
+    ghost.health = 42.;
+    ghost.spawn_weights = [Biome::Meadow, Biome::Wastes, Biome::Forest]
+        .into_iter()
+        .map(|biome| (biome, 1))
+        .collect();
+    for enemy in &mut registry.kinds {
+        enemy.spawn_weights.clear();
+    }
+    registry.kinds.push(ghost);
+
+    advance(&mut app, 600);
+
+    let ghost = kind(&app, "ghost");
+    let enemies: Vec<(EnemyKind, f32)> = app
+        .world_mut()
+        .query::<(&EnemyKind, &Enemy)>()
+        .iter(app.world())
+        .map(|(kind, enemy)| (*kind, enemy.health))
+        .collect();
+    assert!(!enemies.is_empty());
+    assert!(enemies.iter().all(|enemy| *enemy == (ghost, 42.)));
+}
+
+#[test]
+fn live_enemies_keep_their_kind_when_the_registry_is_reordered() {
+    let mut app = test_app();
+    let brute = kind(&app, "brute");
+    let enemy = spawn_kind(&mut app, "brute", Vec2::new(300., 0.), 1000.);
+    advance(&mut app, 1);
+
+    let handle = app.world().resource::<EnemyKinds>().handle.clone();
+    let mut registries = app.world_mut().resource_mut::<Assets<EnemyRegistry>>();
+    registries.get_mut(&handle).unwrap().kinds.rotate_left(1);
+    advance(&mut app, 2);
+
+    let kind = *app.world().get::<EnemyKind>(enemy).unwrap();
+    assert_ne!(kind, brute);
+    assert_eq!(app.world().resource::<EnemyKinds>().get(kind).name, "brute");
+}
+
+#[test]
+fn invalid_registries_are_ignored() {
+    let breakages: [fn(&mut EnemyRegistry); 7] = [
+        |registry| registry.kinds.clear(),
+        |registry| registry.kinds[0].health = 0.,
+        |registry| registry.kinds[0].health = f32::NAN,
+        |registry| registry.kinds[0].sprite.frames = 0,
+        |registry| registry.kinds[0].death.frame_size.x = 0,
+        |registry| {
+            let ranged = registry
+                .kinds
+                .iter_mut()
+                .find_map(|enemy| enemy.ranged.as_mut());
+            ranged.unwrap().cooldown = f32::INFINITY;
+        },
+        |registry| {
+            let boss = registry
+                .kinds
+                .iter_mut()
+                .find_map(|enemy| enemy.boss.as_mut());
+            boss.unwrap().phases[0].cooldown = -1.;
+        },
+    ];
+    for breakage in breakages {
+        let mut app = test_app();
+        let before = app.world().resource::<EnemyKinds>().registry.clone();
+
+        let handle = app.world().resource::<EnemyKinds>().handle.clone();
+        let mut registries = app.world_mut().resource_mut::<Assets<EnemyRegistry>>();
+        breakage(registries.get_mut(&handle).unwrap());
+        advance(&mut app, 2);
+
+        assert_eq!(app.world().resource::<EnemyKinds>().registry, before);
+    }
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_hell::{
+    enemy::flow_field::FlowField,
+    map::{definition::MapDefinition, ActiveMap, MapConfig},
+    Enemy,
+};
+use common::*;
+
+// A wall of trees between the player and anything spawned east of it.
+fn wall() -> impl Iterator<Item = IVec2> {
+    (-6..=6).map(|y| IVec2::new(5, y))
+}
+
+fn app_with_wall() -> App {
+    let mut app = test_app();
+    app.world_mut().resource_mut::<ActiveMap>().definition = MapDefinition {
+        scatter_trees: false,
+        scatter_props: false,
+        trees: wall().collect(),
+        ..default()
+    };
+    app.update();
+    app
+}
+
+#[test]
+fn field_leads_downhill_around_obstacles() {
+    let mut field = FlowField::default();
+    let walls: Vec<IVec2> = wall().collect();
+    // A tile boxed in on every side.
+    let enclosed = IVec2::new(-10, -10);
+    let boxed =
+        |tile: IVec2| tile != enclosed && (tile - enclosed).abs().max_element() == 1;
+    field.build(IVec2::ZERO, 32., |tile| {
+        walls.contains(&tile) || boxed(tile)
+    });
+
+    assert_eq!(field.cost(IVec2::ZERO), Some(0));
+    assert_eq!(field.cost(enclosed), None);
+    assert_eq!(field.cost(IVec2::new(5, 0)), None);
+    // Straight through the wall would cost 60, the way round costs more.
+    assert!(field.cost(IVec2::new(6, 0)).unwrap() > 60);
+
+    // Next to the wall the field points along it rather than into it.
+    let heading = field.heading(Vec2::new(6.5, 0.5) * 32.).unwrap();
+    assert!(heading.y.abs() > 0.5);
+    // Far from any obstacle enemies are left to head straight for the player.
+    assert_eq!(field.heading(Vec2::new(30.5, 30.5) * 32.), None);
+}
+
+#[test]
+fn enemies_path_around_a_wall() {
+    let mut app = app_with_wall();
+    let tile_size = MapConfig::default().tile_size;
+    let enemy = spawn_enemy(&mut app, Vec2::new(9.5, 0.5) * tile_size, 10000.);
+
+    advance(&mut app, 600);
+
+    let position = app.world().get::<Transform>(enemy).unwrap().translation;
+    assert!(position.x < 5. * tile_size);
+    assert!(app.world().get::<Enemy>(enemy).is_some());
+}
+
+#[test]
+fn field_refreshes_on_a_timer() {
+    let mut app = app_with_wall();
+    let origin = app.world().resource::<FlowField>().origin();
+
+    teleport_player(&mut app, Vec2::new(-320., 320.));
+    app.update();
+    assert_eq!(app.world().resource::<FlowField>().origin(), origin);
+
+    advance(&mut app, 30);
+    assert_eq!(
+        app.world().resource::<FlowField>().origin(),
+        IVec2::new(-10, 10)
+    );
+}
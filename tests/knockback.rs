@@ -0,0 +1,104 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_hell::{
+    enemy::elites::{Affix, Elite},
+    Attack, Enemy,
+};
+use common::*;
+
+fn hit(app: &mut App, position: Vec2, knockback: f32) {
+    app.world_mut().spawn((
+        Attack {
+            knockback,
+            ..Attack::new()
+        },
+        Transform::from_translation(position.extend(0.)),
+    ));
+}
+
+fn x(app: &App, enemy: Entity) -> f32 {
+    app.world().get::<Transform>(enemy).unwrap().translation.x
+}
+
+#[test]
+fn hits_knock_enemies_away_from_the_attack() {
+    let mut app = test_app();
+    let enemy = spawn_kind(&mut app, "blob", Vec2::new(200., 0.), 1000.);
+    hit(&mut app, Vec2::new(170., 0.), 400.);
+
+    advance(&mut app, 10);
+
+    assert!(x(&app, enemy) > 220., "{}", x(&app, enemy));
+}
+
+#[test]
+fn knockback_is_a_weapon_stat() {
+    let mut app = test_app();
+    let enemy = spawn_kind(&mut app, "blob", Vec2::new(200., 0.), 1000.);
+    hit(&mut app, Vec2::new(170., 0.), 0.);
+
+    advance(&mut app, 10);
+
+    // Without any knockback the enemy keeps coming.
+    assert!(x(&app, enemy) < 200.);
+}
+
+#[test]
+fn heavier_kinds_are_pushed_less() {
+    let mut app = test_app();
+    let runner = spawn_kind(&mut app, "runner", Vec2::new(200., 0.), 1000.);
+    let brute = spawn_kind(&mut app, "brute", Vec2::new(-200., 0.), 1000.);
+    hit(&mut app, Vec2::new(180., 0.), 400.);
+    hit(&mut app, Vec2::new(-180., 0.), 400.);
+
+    advance(&mut app, 3);
+
+    let runner_pushed = x(&app, runner) - 200.;
+    let brute_pushed = -200. - x(&app, brute);
+    assert!(
+        runner_pushed > brute_pushed * 2.,
+        "{runner_pushed} {brute_pushed}"
+    );
+}
+
+#[test]
+fn knocked_back_enemies_recover_and_close_in_again() {
+    let mut app = test_app();
+    let enemy = spawn_kind(&mut app, "blob", Vec2::new(200., 0.), 1000.);
+    hit(&mut app, Vec2::new(170., 0.), 400.);
+
+    advance(&mut app, 40);
+    let furthest = x(&app, enemy);
+    advance(&mut app, 60);
+
+    assert!(x(&app, enemy) < furthest - 20.);
+}
+
+#[test]
+fn hit_enemies_flash_then_get_their_colour_back() {
+    let mut app = test_app();
+    let enemy = spawn_kind(&mut app, "brute", Vec2::new(300., 0.), 1000.);
+    app.world_mut().entity_mut(enemy).insert(Elite {
+        affixes: vec![Affix::Fast],
+    });
+    advance(&mut app, 1);
+    let colour = app.world().get::<Sprite>(enemy).unwrap().color;
+
+    hit(&mut app, Vec2::new(280., 0.), 0.);
+    advance(&mut app, 2);
+    assert_ne!(app.world().get::<Sprite>(enemy).unwrap().color, colour);
+
+    // The attack keeps hitting until it ends half a second later.
+    advance(&mut app, 60);
+    assert!(app.world().get::<Enemy>(enemy).unwrap().health < 1000.);
+    let after = app.world().get::<Sprite>(enemy).unwrap().color;
+    assert!(
+        after
+            .to_linear()
+            .to_vec4()
+            .distance(colour.to_linear().to_vec4())
+            < 1e-4,
+        "{after:?} {colour:?}"
+    );
+}
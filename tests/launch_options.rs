@@ -0,0 +1,101 @@
+mod common;
+
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use bevy_hell::{launch::LaunchOptions, Player};
+use common::*;
+
+fn parse(args: &str) -> Result<LaunchOptions, String> {
+    LaunchOptions::parse(args.split_whitespace().map(String::from))
+}
+
+#[test]
+fn parses_every_option() {
+    let options = parse(
+        "--seed 7 --headless --ticks 600 --tick-rate 30 --start-level 3 --god \
+         --no-audio --window 800x600 --record run.bhr --debug-overlay \
+         --map maps/clearing.map.ron",
+    )
+    .unwrap();
+
+    assert_eq!(
+        options,
+        LaunchOptions {
+            seed: Some(7),
+            headless: true,
+            ticks: Some(600),
+            tick_rate: Some(30.),
+            start_level: Some(3),
+            god: true,
+            no_audio: true,
+            window: Some((800., 600.)),
+            record: Some(PathBuf::from("run.bhr")),
+            debug_overlay: true,
+            map: Some(PathBuf::from("maps/clearing.map.ron")),
+            ..default()
+        }
+    );
+    assert_eq!(parse("").unwrap(), LaunchOptions::default());
+}
+
+#[test]
+fn rejects_bad_arguments() {
+    assert!(parse("--fly").is_err());
+    assert!(parse("--seed").is_err());
+    assert!(parse("--seed many").is_err());
+    assert!(parse("--window 800").is_err());
+    assert!(parse("--window 0x600").is_err());
+    assert!(parse("--start-level 0").is_err());
+    assert!(parse("--start-level 24").is_err());
+    assert!(parse("--tick-rate -5").is_err());
+    assert!(parse("--tick-rate nan").is_err());
+    assert!(parse("--tick-rate inf").is_err());
+}
+
+#[test]
+fn the_highest_start_level_keeps_a_sane_threshold() {
+    let player = Player::at_level(23);
+    assert_eq!(player.level, 23);
+    assert!(player.next_level > player.xp);
+    assert_eq!(
+        Player::at_level(30).xp_to_next_level(),
+        u32::MAX,
+        "thresholds saturate rather than overflow"
+    );
+}
+
+#[test]
+fn start_level_applies_to_the_spawned_player() {
+    let mut app = test_app_with(LaunchOptions {
+        start_level: Some(3),
+        ..default()
+    });
+
+    assert_eq!(player(&mut app).level, 3);
+    assert_eq!(player(&mut app).xp, 2000);
+    assert_eq!(player(&mut app).next_level, 4000);
+}
+
+#[test]
+fn god_mode_ignores_damage_and_enables_the_xp_key() {
+    let mut app = test_app_with(LaunchOptions {
+        god: true,
+        ..default()
+    });
+    spawn_enemy(&mut app, Vec2::new(10., 0.), 1000.);
+    press_key(&mut app, KeyCode::NumpadAdd);
+    advance(&mut app, 5);
+
+    assert_eq!(player(&mut app).health, 100.);
+    assert!(player(&mut app).xp >= 500);
+}
+
+#[test]
+fn xp_key_does_nothing_without_god_mode() {
+    let mut app = test_app();
+    press_key(&mut app, KeyCode::NumpadAdd);
+    advance(&mut app, 5);
+
+    assert_eq!(player(&mut app).xp, 0);
+}
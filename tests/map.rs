@@ -0,0 +1,292 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_hell::{
+    enemy::components::ENEMY_RADIUS,
+    launch::LaunchOptions,
+    map::{
+        chunks::{ground_at, Chunk, Ground, LoadedChunks},
+        definition::{MapDefinition, SpawnZone},
+        trees::TREE_RADIUS,
+        ActiveMap, MapConfig,
+    },
+    player::components::PLAYER_RADIUS,
+    rng::{GameRng, RngStream},
+    Enemy, GameState, Player,
+};
+use common::*;
+
+fn chunk_coords(app: &mut App) -> Vec<IVec2> {
+    let mut coords: Vec<IVec2> = app
+        .world_mut()
+        .query::<&Chunk>()
+        .iter(app.world())
+        .map(|chunk| chunk.0)
+        .collect();
+    coords.sort_by_key(|coord| (coord.x, coord.y));
+    coords
+}
+
+#[test]
+fn chunks_surround_the_player() {
+    let mut app = test_app();
+    let config = app.world().resource::<MapConfig>().clone();
+    let side = (2 * config.view_distance + 1) as usize;
+
+    assert_eq!(count::<Chunk>(&mut app), side * side);
+    assert_eq!(
+        count::<Ground>(&mut app),
+        side * side * (config.chunk_size * config.chunk_size) as usize
+    );
+    assert!(chunk_coords(&mut app).contains(&IVec2::ZERO));
+}
+
+#[test]
+fn moving_away_streams_chunks_and_keeps_the_count_bounded() {
+    let mut app = test_app();
+    let before = count::<Ground>(&mut app);
+    let far = app.world().resource::<MapConfig>().chunk_world_size() * 20.;
+
+    teleport_player(&mut app, Vec2::new(far, far));
+    advance(&mut app, 2);
+
+    let coords = chunk_coords(&mut app);
+    assert!(!coords.contains(&IVec2::ZERO));
+    assert!(coords.contains(&IVec2::new(20, 20)));
+    assert_eq!(count::<Ground>(&mut app), before);
+    assert_eq!(app.world().resource::<LoadedChunks>().0.len(), coords.len());
+}
+
+#[test]
+fn ground_is_seeded_and_mixes_grass_and_dirt() {
+    let tiles: Vec<IVec2> = (0..64)
+        .flat_map(|y| (0..64).map(move |x| IVec2::new(x, y)))
+        .collect();
+    let ground: Vec<Ground> = tiles.iter().map(|tile| ground_at(SEED, *tile)).collect();
+
+    assert!(ground.contains(&Ground::Grass));
+    assert!(ground.contains(&Ground::Dirt));
+    assert_eq!(
+        ground,
+        tiles
+            .iter()
+            .map(|tile| ground_at(SEED, *tile))
+            .collect::<Vec<_>>()
+    );
+    assert_ne!(
+        ground,
+        tiles
+            .iter()
+            .map(|tile| ground_at(SEED + 1, *tile))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn changing_the_config_rebuilds_chunks() {
+    let mut app = test_app();
+    app.world_mut().resource_mut::<MapConfig>().view_distance = 1;
+    advance(&mut app, 1);
+
+    assert_eq!(count::<Chunk>(&mut app), 9);
+}
+
+fn first_tree_east_of_spawn() -> Vec2 {
+    let map = MapDefinition::default();
+    let tile = (0..1000)
+        .map(|x| IVec2::new(x, 0))
+        .find(|tile| map.tree_at(SEED, *tile))
+        .expect("a tree along the x axis");
+    (tile.as_vec2() + 0.5) * MapConfig::default().tile_size
+}
+
+fn position(app: &App, entity: Entity) -> Vec2 {
+    app.world()
+        .get::<Transform>(entity)
+        .unwrap()
+        .translation
+        .truncate()
+}
+
+#[test]
+fn player_cannot_walk_through_a_tree() {
+    let tree = first_tree_east_of_spawn();
+    let mut app = test_app();
+    teleport_player(&mut app, tree - Vec2::new(60., 0.));
+    let player = app
+        .world_mut()
+        .query_filtered::<Entity, With<Player>>()
+        .single(app.world());
+
+    press_key(&mut app, KeyCode::KeyD);
+    for _ in 0..120 {
+        advance(&mut app, 1);
+        let distance = position(&app, player).distance(tree);
+        assert!(distance >= PLAYER_RADIUS + TREE_RADIUS - 1e-3);
+    }
+    assert!(position(&app, player).x < tree.x);
+}
+
+#[test]
+fn enemies_cannot_walk_through_a_tree() {
+    let tree = first_tree_east_of_spawn();
+    let mut app = test_app();
+    teleport_player(&mut app, tree - Vec2::new(200., 0.));
+    let enemy = spawn_enemy(&mut app, tree + Vec2::new(60., 0.), 1000.);
+
+    for _ in 0..120 {
+        advance(&mut app, 1);
+        let distance = position(&app, enemy).distance(tree);
+        assert!(distance >= ENEMY_RADIUS + TREE_RADIUS - 1e-3);
+    }
+}
+
+fn app_with_map(path: &str) -> App {
+    test_app_with(LaunchOptions {
+        map: Some(path.into()),
+        ..default()
+    })
+}
+
+#[test]
+fn spawn_points_stay_inside_their_zones_and_the_bounds() {
+    let corner = Rect::new(400., 400., 600., 600.);
+    let map = MapDefinition {
+        spawn_zones: vec![
+            SpawnZone::Area(corner),
+            SpawnZone::Ring {
+                inner: 1200.,
+                outer: 2000.,
+            },
+        ],
+        bounds: Some(Rect::new(-800., -800., 800., 800.)),
+        ..default()
+    };
+    let mut rng = GameRng::new(SEED);
+    let rng = rng.stream(RngStream::EnemySpawn);
+
+    let points: Vec<Vec2> = (0..200).map(|_| map.spawn_point(rng, Vec2::ZERO)).collect();
+
+    assert!(points.iter().all(|point| map.in_bounds(*point)));
+    assert!(points.iter().any(|point| corner.contains(*point)));
+    // Points from the ring all lie beyond the bounds and end up on its edge.
+    assert!(points
+        .iter()
+        .filter(|point| !corner.contains(**point))
+        .all(|point| point.x.abs() == 800. || point.y.abs() == 800.));
+}
+
+#[test]
+fn map_file_is_loaded_and_instantiated() {
+    let mut app = app_with_map("maps/clearing.map.ron");
+    let map = app.world().resource::<ActiveMap>().definition.clone();
+
+    assert_ne!(map, MapDefinition::default());
+    assert_eq!(map.ground_at(SEED, IVec2::ZERO), Ground::Dirt);
+    assert_eq!(map.ground_at(SEED, IVec2::new(5, 5)), Ground::Grass);
+    assert!(map.tree_at(SEED, IVec2::new(-20, -20)));
+    assert!(!map.tree_at(SEED, IVec2::new(5, 5)));
+
+    let bounds = map.bounds.unwrap();
+    assert!(count::<Ground>(&mut app) > 0);
+    // Headless apps do not propagate transforms, so add the chunk offset here.
+    let tiles: Vec<Vec2> = app
+        .world_mut()
+        .query_filtered::<(&Parent, &Transform), With<Ground>>()
+        .iter(app.world())
+        .map(|(parent, transform)| {
+            let chunk = app.world().get::<Transform>(parent.get()).unwrap();
+            (chunk.translation + transform.translation).truncate()
+        })
+        .collect();
+    assert!(tiles.iter().any(|tile| tile.x < 0.));
+    assert!(tiles.iter().all(|tile| bounds.contains(*tile)));
+
+    advance(&mut app, 600);
+    let enemies: Vec<Vec2> = app
+        .world_mut()
+        .query_filtered::<&Transform, With<Enemy>>()
+        .iter(app.world())
+        .map(|transform| transform.translation.truncate())
+        .collect();
+    assert!(!enemies.is_empty());
+    assert!(enemies.iter().all(|enemy| bounds.contains(*enemy)));
+}
+
+#[test]
+fn pickups_appear_at_the_map_pickup_points() {
+    let map = MapDefinition {
+        pickup_points: vec![Vec2::new(100., 0.), Vec2::new(-100., 0.)],
+        ..default()
+    };
+    let mut rng = GameRng::new(SEED);
+    let rng = rng.stream(RngStream::Pickups);
+
+    for _ in 0..20 {
+        let point = map.pickup_point(rng, Vec2::new(5000., 5000.));
+        assert!(map.pickup_points.contains(&point));
+    }
+}
+
+#[test]
+fn missing_map_file_falls_back_to_the_endless_map() {
+    let app = app_with_map("maps/missing.map.ron");
+
+    assert_eq!(state(&app), GameState::Running);
+    assert_eq!(
+        app.world().resource::<ActiveMap>().definition,
+        MapDefinition::default()
+    );
+}
+
+#[test]
+fn invalid_maps_are_ignored() {
+    let ring = |inner, outer| MapDefinition {
+        spawn_zones: vec![SpawnZone::Ring { inner, outer }],
+        ..default()
+    };
+    let area = |rect| MapDefinition {
+        spawn_zones: vec![SpawnZone::Area(rect)],
+        ..default()
+    };
+    let invalid = [
+        ring(500., 500.),
+        ring(900., 400.),
+        ring(f32::NAN, 400.),
+        area(Rect {
+            min: Vec2::new(0., 0.),
+            max: Vec2::new(100., 0.),
+        }),
+        area(Rect {
+            min: Vec2::new(100., 100.),
+            max: Vec2::new(0., 200.),
+        }),
+        MapDefinition {
+            bounds: Some(Rect {
+                min: Vec2::ZERO,
+                max: Vec2::new(f32::INFINITY, 10.),
+            }),
+            ..default()
+        },
+        MapDefinition {
+            pickup_points: vec![Vec2::NAN],
+            ..default()
+        },
+    ];
+
+    let mut app = app_with_map("maps/clearing.map.ron");
+    let clearing = app.world().resource::<ActiveMap>().definition.clone();
+    let handle = app.world().resource::<ActiveMap>().handle.clone();
+    for map in invalid {
+        assert!(map.validate().is_err(), "{map:?}");
+        app.world_mut()
+            .resource_mut::<Assets<MapDefinition>>()
+            .insert(&handle, map);
+
+        advance(&mut app, 60);
+
+        assert_eq!(app.world().resource::<ActiveMap>().definition, clearing);
+    }
+    assert!(MapDefinition::default().validate().is_ok());
+    assert!(clearing.validate().is_ok());
+}
@@ -0,0 +1,122 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_hell::{
+    animation::AnimationTimerOnce,
+    map::{definition::MapDefinition, ActiveMap, MapConfig},
+    pickups::Pickup,
+    props::{roll_loot, Loot, Prop, PropKind, PropTile},
+    rng::{GameRng, RngStream},
+    Attack,
+};
+use common::*;
+
+fn app_with_props(props: &[(IVec2, PropKind)]) -> App {
+    let mut app = test_app();
+    app.world_mut().resource_mut::<ActiveMap>().definition = MapDefinition {
+        scatter_trees: false,
+        scatter_props: false,
+        props: props.iter().copied().collect(),
+        ..default()
+    };
+    app.update();
+    app
+}
+
+fn tile_centre(tile: IVec2) -> Vec3 {
+    ((tile.as_vec2() + 0.5) * MapConfig::default().tile_size).extend(1.)
+}
+
+fn props_at(app: &mut App, tile: IVec2) -> usize {
+    app.world_mut()
+        .query_filtered::<&PropTile, With<Prop>>()
+        .iter(app.world())
+        .filter(|prop| prop.0 == tile)
+        .count()
+}
+
+#[test]
+fn props_are_scattered_around_the_map() {
+    let mut app = test_app();
+    assert!(count::<Prop>(&mut app) > 0);
+
+    let mut app = app_with_props(&[]);
+    assert_eq!(count::<Prop>(&mut app), 0);
+}
+
+#[test]
+fn attacks_break_props_which_stay_broken() {
+    let tile = IVec2::new(6, 0);
+    let mut app = app_with_props(&[(tile, PropKind::Crate)]);
+    assert_eq!(props_at(&mut app, tile), 1);
+
+    app.world_mut().spawn((
+        Attack::new(),
+        Transform::from_translation(tile_centre(tile)),
+    ));
+    advance(&mut app, 5);
+
+    assert_eq!(props_at(&mut app, tile), 0);
+    assert_eq!(count::<AnimationTimerOnce>(&mut app), 1);
+
+    let far = MapConfig::default().chunk_world_size() * 20.;
+    teleport_player(&mut app, Vec2::splat(far));
+    advance(&mut app, 2);
+    teleport_player(&mut app, Vec2::ZERO);
+    advance(&mut app, 2);
+
+    assert_eq!(props_at(&mut app, tile), 0);
+    assert_eq!(count::<PropTile>(&mut app), 0);
+}
+
+#[test]
+fn broken_props_can_drop_pickups() {
+    let tiles: Vec<(IVec2, PropKind)> = (0..8)
+        .map(|x| (IVec2::new(x * 4 - 14, 8), PropKind::Urn))
+        .collect();
+    let mut app = app_with_props(&tiles);
+    for (tile, _) in &tiles {
+        app.world_mut().spawn((
+            Attack::new(),
+            Transform::from_translation(tile_centre(*tile)),
+        ));
+    }
+    advance(&mut app, 5);
+
+    assert_eq!(count::<Prop>(&mut app), 0);
+    assert!(count::<Pickup>(&mut app) > 0);
+}
+
+#[test]
+fn streaming_props_reuses_one_atlas_layout() {
+    let mut app = test_app();
+    let layouts = |app: &App| app.world().resource::<Assets<TextureAtlasLayout>>().len();
+    let before = layouts(&app);
+
+    let step = MapConfig::default().chunk_world_size();
+    for i in 1..=4 {
+        teleport_player(&mut app, Vec2::new(step * i as f32, 0.));
+        advance(&mut app, 2);
+    }
+
+    assert!(count::<Prop>(&mut app) > 0);
+    assert_eq!(layouts(&app), before);
+}
+
+#[test]
+fn drop_tables_respect_their_weights() {
+    let mut rng = GameRng::new(SEED);
+    let rng = rng.stream(RngStream::Props);
+
+    let table = [(Loot::Nothing, 0), (Loot::HealthPotion, 1)];
+    assert!((0..50).all(|_| roll_loot(&table, rng) == Loot::HealthPotion));
+
+    let rolls: Vec<Loot> = (0..400)
+        .map(|_| roll_loot(PropKind::Urn.drop_table(), rng))
+        .collect();
+    let potions = rolls
+        .iter()
+        .filter(|loot| **loot == Loot::HealthPotion)
+        .count();
+    assert!((250..350).contains(&potions), "{potions} potions");
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_hell::{
+    bullet::{Bullet, Faction},
+    enemy::kinds::EnemyKinds,
+    launch::LaunchOptions,
+    map::{definition::MapDefinition, ActiveMap},
+    Enemy,
+};
+use common::*;
+
+// An open map, so nothing but the spitter gets in the way.
+fn open_app(options: LaunchOptions) -> App {
+    let mut app = test_app_with(options);
+    app.world_mut().resource_mut::<ActiveMap>().definition = MapDefinition {
+        scatter_trees: false,
+        scatter_props: false,
+        ..default()
+    };
+    app.update();
+    app
+}
+
+fn range(app: &App) -> f32 {
+    let kinds = app.world().resource::<EnemyKinds>();
+    let spitter = kinds.registry.find("spitter").unwrap();
+    kinds.get(spitter).ranged.as_ref().unwrap().range
+}
+
+fn distance_to_player(app: &App, enemy: Entity) -> f32 {
+    let position = app.world().get::<Transform>(enemy).unwrap().translation;
+    position.truncate().length()
+}
+
+#[test]
+fn spitters_keep_their_distance() {
+    let mut app = open_app(LaunchOptions {
+        god: true,
+        ..default()
+    });
+    let range = range(&app);
+    let far = spawn_kind(&mut app, "spitter", Vec2::new(600., 0.), 10000.);
+    let near = spawn_kind(&mut app, "spitter", Vec2::new(0., 60.), 10000.);
+
+    advance(&mut app, 420);
+
+    for enemy in [far, near] {
+        let distance = distance_to_player(&app, enemy);
+        assert!(
+            distance > range * 0.7 && distance < range * 1.05,
+            "{distance}"
+        );
+    }
+}
+
+#[test]
+fn spitters_shoot_the_player() {
+    let mut app = open_app(LaunchOptions::default());
+    spawn_kind(&mut app, "spitter", Vec2::new(200., 0.), 10000.);
+
+    advance(&mut app, 35);
+    assert_eq!(count_bullets(&mut app, Faction::Enemy), 1);
+
+    advance(&mut app, 60);
+    assert_eq!(count_bullets(&mut app, Faction::Enemy), 0);
+    assert!(player(&mut app).health < 100.);
+}
+
+#[test]
+fn player_bullets_only_hit_enemies() {
+    let mut app = open_app(LaunchOptions::default());
+    let enemy = spawn_enemy(&mut app, Vec2::new(150., 0.), 100.);
+    app.world_mut().spawn((
+        Transform::from_xyz(0., 0., 1.),
+        Bullet::new(Vec2::X, 400., 10., Faction::Player),
+    ));
+
+    advance(&mut app, 30);
+
+    assert_eq!(app.world().get::<Enemy>(enemy).unwrap().health, 90.);
+    assert_eq!(player(&mut app).health, 100.);
+    assert_eq!(count_bullets(&mut app, Faction::Player), 0);
+}
@@ -0,0 +1,35 @@
+use std::{io::ErrorKind, time::Duration};
+
+use bevy_hell::replay::{Replay, ReplayFrame};
+
+fn replay(frames: usize) -> Replay {
+    Replay {
+        seed: 7,
+        timestep: Duration::from_secs_f64(1. / 60.),
+        frames: vec![
+            ReplayFrame {
+                delta: Duration::from_millis(16),
+                actions: 0b101,
+                level_up: None,
+            };
+            frames
+        ],
+    }
+}
+
+#[test]
+fn replays_survive_a_round_trip() {
+    let replay = replay(600);
+    assert_eq!(Replay::decode(&replay.encode()).unwrap(), replay);
+}
+
+#[test]
+fn absurd_run_lengths_are_rejected() {
+    let mut bytes = replay(1).encode();
+    // The single run's length comes right after the header and run count.
+    let count = bytes.len() - 11;
+    bytes[count..count + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+
+    let err = Replay::decode(&bytes).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_hell::{
+    animation::AnimationTimerOnce,
+    attacks::AttackSpawner,
+    enemy::components::{AttackTimer, SpawnTimer as EnemySpawnTimer},
+    pickups::{Pickup, SpawnTimer as PickupSpawnTimer},
+    Attack, Enemy, GameState, GlobalStopwatch, Player, RunScoped,
+};
+use common::*;
+
+#[derive(Debug, PartialEq)]
+struct RunSnapshot {
+    elapsed: f32,
+    enemy_spawn_timers: Vec<Timer>,
+    pickup_spawn_timer: Timer,
+    enemy_attack_timer: Timer,
+    attack_cooldown: Timer,
+    next_attack: Timer,
+    attack_i: u32,
+    player: (f32, u32, u32, u32, Vec3),
+    enemies: Vec<(Vec3, f32)>,
+    attacks: usize,
+    pickups: usize,
+    corpses: usize,
+    run_scoped: usize,
+}
+
+fn snapshot(app: &mut App) -> RunSnapshot {
+    let world = app.world_mut();
+    let spawner = world.resource::<AttackSpawner>();
+    let (attack_cooldown, next_attack, attack_i) = (
+        spawner.cooldown.clone(),
+        spawner.next_attack.clone(),
+        spawner.attack_i,
+    );
+    let (player, transform) = world.query::<(&Player, &Transform)>().single(world);
+    let player = (
+        player.health,
+        player.xp,
+        player.level,
+        player.next_level,
+        transform.translation,
+    );
+    let mut enemies: Vec<(Vec3, f32)> = world
+        .query::<(&Transform, &Enemy)>()
+        .iter(world)
+        .map(|(transform, enemy)| (transform.translation, enemy.health))
+        .collect();
+    enemies.sort_by(|a, b| a.0.to_array().partial_cmp(&b.0.to_array()).unwrap());
+
+    RunSnapshot {
+        elapsed: world.resource::<GlobalStopwatch>().clock.elapsed_secs(),
+        enemy_spawn_timers: world.resource::<EnemySpawnTimer>().countdowns.clone(),
+        pickup_spawn_timer: world.resource::<PickupSpawnTimer>().countdown.clone(),
+        enemy_attack_timer: world.resource::<AttackTimer>().countdown.clone(),
+        attack_cooldown,
+        next_attack,
+        attack_i,
+        player,
+        enemies,
+        attacks: count::<Attack>(app),
+        pickups: count::<Pickup>(app),
+        corpses: count::<AnimationTimerOnce>(app),
+        run_scoped: count::<RunScoped>(app),
+    }
+}
+
+#[test]
+fn restarted_run_matches_a_fresh_one() {
+    let mut fresh = test_app();
+    advance(&mut fresh, 600);
+    let expected = snapshot(&mut fresh);
+    assert!(!expected.enemies.is_empty());
+
+    let mut restarted = test_app();
+    advance(&mut restarted, 1200);
+    restarted
+        .world_mut()
+        .spawn((Transform::from_xyz(300., 0., 1.), Pickup));
+    spawn_enemy(&mut restarted, Vec2::new(600., 0.), 0.);
+    player(&mut restarted).health = 0.;
+    advance(&mut restarted, 2);
+    assert_eq!(state(&restarted), GameState::GameOver);
+    assert!(count::<AnimationTimerOnce>(&mut restarted) > 0);
+
+    press_key(&mut restarted, KeyCode::KeyR);
+    // The key is read on this frame. The new run starts, and plays its first
+    // tick, on the next one, just like the last frame of `test_app` did for
+    // the fresh run.
+    restarted.update();
+    advance(&mut restarted, 601);
+
+    assert_eq!(state(&restarted), GameState::Running);
+    assert_eq!(snapshot(&mut restarted), expected);
+}
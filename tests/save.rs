@@ -0,0 +1,96 @@
+mod common;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+use bevy_hell::{
+    headless::HeadlessPlugin, rng::GameRng, save::SavePlugin, BevyHellPlugins, Enemy,
+    GameState, GlobalStopwatch, Player,
+};
+use common::*;
+
+fn save_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir()
+        .join(format!("bevy_hell_test_{}", std::process::id()))
+        .join(name);
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn app_with_save(path: &Path) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        HeadlessPlugin,
+        BevyHellPlugins::headless(),
+        SavePlugin {
+            path: path.to_path_buf(),
+        },
+    ))
+    .insert_resource(GameRng::new(SEED));
+    while state(&app) == GameState::Loading {
+        app.update();
+    }
+    app
+}
+
+fn enemies(app: &mut App) -> Vec<(Vec3, f32)> {
+    let mut enemies: Vec<(Vec3, f32)> = app
+        .world_mut()
+        .query::<(&Transform, &Enemy)>()
+        .iter(app.world())
+        .map(|(transform, enemy)| (transform.translation, enemy.health))
+        .collect();
+    enemies.sort_by(|a, b| a.0.to_array().partial_cmp(&b.0.to_array()).unwrap());
+    enemies
+}
+
+fn elapsed(app: &App) -> f32 {
+    app.world()
+        .resource::<GlobalStopwatch>()
+        .clock
+        .elapsed_secs()
+}
+
+#[test]
+fn saved_run_resumes_paused_with_the_same_state() {
+    let path = save_path("resume.ron");
+    let mut app = app_with_save(&path);
+    advance(&mut app, 600);
+    player(&mut app).xp = 250;
+    press_key(&mut app, KeyCode::Space);
+    advance(&mut app, 2);
+    assert_eq!(state(&app), GameState::Paused);
+
+    press_key(&mut app, KeyCode::KeyQ);
+    app.update();
+    assert!(path.exists());
+    let saved_enemies = enemies(&mut app);
+    assert!(!saved_enemies.is_empty());
+
+    let mut resumed = app_with_save(&path);
+    resumed.update();
+
+    assert_eq!(state(&resumed), GameState::Paused);
+    assert_eq!(count::<Player>(&mut resumed), 1);
+    assert_eq!(player(&mut resumed).xp, 250);
+    assert_eq!(enemies(&mut resumed), saved_enemies);
+    assert_eq!(elapsed(&resumed), elapsed(&app));
+    assert!(!path.exists(), "a save should only be resumed once");
+}
+
+#[test]
+fn corrupt_save_starts_a_fresh_run() {
+    let path = save_path("corrupt.ron");
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, "not a save").unwrap();
+
+    let mut app = app_with_save(&path);
+    app.update();
+
+    assert_eq!(state(&app), GameState::Running);
+    assert_eq!(player(&mut app).xp, 0);
+    assert_eq!(count::<Enemy>(&mut app), 0);
+}
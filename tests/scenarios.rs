@@ -0,0 +1,120 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_hell::{
+    animation::AnimationTimerOnce,
+    pickups::Pickup,
+    player::levelup::{LevelUpMenu, MenuButtonAction},
+    Enemy, GameState, GlobalStopwatch, Player,
+};
+use common::*;
+
+fn reach_level_up_screen(app: &mut App) {
+    let next_level = player(app).next_level;
+    player(app).xp = next_level;
+    advance(app, 2);
+}
+
+#[test]
+fn gaining_enough_xp_opens_level_up_screen() {
+    let mut app = test_app();
+
+    reach_level_up_screen(&mut app);
+
+    assert_eq!(state(&app), GameState::LevelUpScreen);
+    assert_eq!(player(&mut app).level, 2);
+    assert_eq!(player(&mut app).next_level, 2000);
+    assert_eq!(count::<LevelUpMenu>(&mut app), 1);
+}
+
+#[test]
+fn level_up_button_applies_modifier_and_resumes() {
+    let mut app = test_app();
+    reach_level_up_screen(&mut app);
+
+    let button = app
+        .world_mut()
+        .query::<(Entity, &MenuButtonAction)>()
+        .iter(app.world())
+        .find(|(_, action)| **action == MenuButtonAction::MovementSpeed)
+        .map(|(entity, _)| entity)
+        .expect("level up menu has a movement speed button");
+    app.world_mut()
+        .entity_mut(button)
+        .insert(Interaction::Pressed);
+    advance(&mut app, 2);
+
+    assert_eq!(state(&app), GameState::Running);
+    assert_eq!(player(&mut app).movement_speed_mod, 0.25);
+    assert_eq!(player(&mut app).attack_speed_mod, 0.);
+    assert_eq!(count::<LevelUpMenu>(&mut app), 0);
+}
+
+#[test]
+fn dead_enemy_leaves_corpse_and_grants_xp() {
+    let mut app = test_app();
+    let enemy = spawn_enemy(&mut app, Vec2::new(300., 300.), 0.);
+
+    advance(&mut app, 1);
+
+    assert!(app.world().get::<Enemy>(enemy).is_none());
+    assert_eq!(player(&mut app).xp, 25);
+    assert_eq!(count::<AnimationTimerOnce>(&mut app), 1);
+}
+
+#[test]
+fn attack_kills_enemy_next_to_player() {
+    let mut app = test_app();
+    let enemy = spawn_enemy(&mut app, Vec2::new(40., 0.), 10.);
+
+    advance(&mut app, 150);
+
+    assert!(app.world().get::<Enemy>(enemy).is_none());
+    assert!(player(&mut app).xp >= 25);
+}
+
+#[test]
+fn pickup_heals_player_up_to_max_health() {
+    let mut app = test_app();
+    player(&mut app).health = 50.;
+    app.world_mut()
+        .spawn((Transform::from_xyz(0., 0., 1.), Pickup));
+
+    advance(&mut app, 1);
+
+    assert_eq!(player(&mut app).health, 75.);
+    assert_eq!(count::<Pickup>(&mut app), 0);
+
+    player(&mut app).health = 90.;
+    app.world_mut()
+        .spawn((Transform::from_xyz(10., 0., 1.), Pickup));
+
+    advance(&mut app, 1);
+
+    assert_eq!(player(&mut app).health, 100.);
+}
+
+#[test]
+fn restart_after_game_over_starts_a_new_run() {
+    let mut app = test_app();
+    let enemy = spawn_enemy(&mut app, Vec2::new(500., 0.), 10.);
+    player(&mut app).health = 0.;
+    advance(&mut app, 2);
+
+    assert_eq!(state(&app), GameState::GameOver);
+    assert_eq!(count::<Player>(&mut app), 0);
+
+    press_key(&mut app, KeyCode::KeyR);
+    advance(&mut app, 2);
+
+    assert_eq!(state(&app), GameState::Running);
+    assert!(app.world().get::<Enemy>(enemy).is_none());
+    assert_eq!(count::<Player>(&mut app), 1);
+    assert_eq!(player(&mut app).health, 100.);
+    let elapsed = app
+        .world()
+        .resource::<GlobalStopwatch>()
+        .clock
+        .elapsed_secs();
+    assert!(elapsed < 0.1, "stopwatch was not reset: {elapsed}");
+}
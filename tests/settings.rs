@@ -0,0 +1,87 @@
+mod common;
+
+use std::{fs, path::PathBuf};
+
+use bevy::prelude::*;
+use bevy_hell::{
+    settings::{DisplayMode, Settings},
+    Player,
+};
+use common::*;
+
+fn settings_path(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("bevy_hell_settings_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn player_x(app: &mut App) -> f32 {
+    app.world_mut()
+        .query_filtered::<&Transform, With<Player>>()
+        .single(app.world())
+        .translation
+        .x
+}
+
+#[test]
+fn missing_settings_file_is_created_with_defaults() {
+    let path = settings_path("missing.ron");
+
+    let settings = Settings::load_or_default(&path);
+
+    assert_eq!(settings, Settings::default());
+    assert_eq!(Settings::load(&path).unwrap(), Settings::default());
+}
+
+#[test]
+fn corrupt_settings_file_falls_back_to_defaults_and_is_kept() {
+    let path = settings_path("corrupt.ron");
+    fs::write(&path, "(master_volume: ").unwrap();
+
+    let settings = Settings::load_or_default(&path);
+
+    assert_eq!(settings, Settings::default());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "(master_volume: ");
+}
+
+#[test]
+fn partial_settings_file_keeps_defaults_for_the_rest() {
+    let path = settings_path("partial.ron");
+    fs::write(
+        &path,
+        "(sfx_volume: 0.1, window: (mode: BorderlessFullscreen), \
+         key_bindings: (pause: [Escape]))",
+    )
+    .unwrap();
+
+    let settings = Settings::load_or_default(&path);
+
+    assert_eq!(settings.sfx_volume, 0.1);
+    assert_eq!(settings.window.mode, DisplayMode::BorderlessFullscreen);
+    assert_eq!(settings.window.width, Settings::default().window.width);
+    assert_eq!(settings.key_bindings.pause, vec![KeyCode::Escape]);
+    assert_eq!(
+        settings.key_bindings.move_up,
+        Settings::default().key_bindings.move_up
+    );
+}
+
+#[test]
+fn rebound_movement_key_moves_the_player() {
+    let mut app = test_app();
+    app.world_mut()
+        .resource_mut::<Settings>()
+        .key_bindings
+        .move_right = vec![KeyCode::KeyL];
+
+    press_key(&mut app, KeyCode::KeyD);
+    advance(&mut app, 10);
+    assert_eq!(player_x(&mut app), 0.);
+
+    press_key(&mut app, KeyCode::KeyL);
+    advance(&mut app, 10);
+    assert!(player_x(&mut app) > 0.);
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_hell::{
+    interpolation::Interpolated,
+    pickups::pickup_bundle,
+    spatial::{SpatialGrid, SpatialHash},
+};
+use common::*;
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+#[test]
+fn spatial_hash_finds_the_same_neighbours_as_a_full_scan() {
+    let mut rng = SmallRng::seed_from_u64(SEED);
+    let points: Vec<(Entity, Vec2)> = (0..500)
+        .map(|i| {
+            let point =
+                Vec2::new(rng.gen_range(-500.0..500.), rng.gen_range(-500.0..500.));
+            (Entity::from_raw(i), point)
+        })
+        .collect();
+    let mut hash = SpatialHash::new(40.);
+    hash.rebuild(points.iter().copied());
+
+    for _ in 0..50 {
+        let centre = Vec2::new(rng.gen_range(-500.0..500.), rng.gen_range(-500.0..500.));
+        let radius = rng.gen_range(1.0..120.);
+        let mut found: Vec<Entity> =
+            hash.within(centre, radius).map(|(e, _)| e).collect();
+        let mut expected: Vec<Entity> = points
+            .iter()
+            .filter(|(_, point)| point.distance(centre) <= radius)
+            .map(|(e, _)| *e)
+            .collect();
+        found.sort();
+        expected.sort();
+        assert_eq!(found, expected);
+    }
+}
+
+#[test]
+fn grid_follows_enemies_and_pickups() {
+    let mut app = test_app();
+    let enemy = spawn_enemy(&mut app, Vec2::new(2000., 0.), 10.);
+    let pickup = app
+        .world_mut()
+        .spawn(pickup_bundle(default(), Vec3::new(0., 300., 1.)))
+        .id();
+    advance(&mut app, 1);
+
+    let near = |app: &App, position: Vec2| -> Vec<Entity> {
+        let grid = app.world().resource::<SpatialGrid>();
+        grid.entities_within(position, 40.).collect()
+    };
+    assert!(near(&app, Vec2::new(0., 300.)).contains(&pickup));
+    assert!(near(&app, Vec2::new(2000., 0.)).contains(&enemy));
+
+    // Gameplay positions live in `Interpolated` between ticks.
+    app.world_mut()
+        .get_mut::<Interpolated>(enemy)
+        .unwrap()
+        .current
+        .x = -2000.;
+    advance(&mut app, 1);
+
+    assert!(!near(&app, Vec2::new(2000., 0.)).contains(&enemy));
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_hell::{enemy::steering::Steering, Enemy};
+use common::*;
+
+fn positions(app: &mut App) -> Vec<Vec2> {
+    app.world_mut()
+        .query_filtered::<&Transform, With<Enemy>>()
+        .iter(app.world())
+        .map(|transform| transform.translation.truncate())
+        .collect()
+}
+
+fn closest_pair(points: &[Vec2]) -> f32 {
+    let mut closest = f32::INFINITY;
+    for (i, a) in points.iter().enumerate() {
+        for b in &points[i + 1..] {
+            closest = closest.min(a.distance(*b));
+        }
+    }
+    closest
+}
+
+// Spawns a pile of enemies on one spot far from the player and lets them chase
+// for a few seconds.
+fn crowd_after_chasing(steering: Steering) -> Vec<Vec2> {
+    let mut app = test_app();
+    app.insert_resource(steering);
+    for _ in 0..30 {
+        spawn_enemy(&mut app, Vec2::new(600., 0.), 1000.);
+    }
+    advance(&mut app, 180);
+    positions(&mut app)
+}
+
+#[test]
+fn stacked_enemies_spread_out() {
+    let spread = crowd_after_chasing(Steering::default());
+    let stacked = crowd_after_chasing(Steering {
+        separation: 0.,
+        alignment: 0.,
+        ..default()
+    });
+
+    assert!(closest_pair(&stacked) < 1.);
+    assert!(closest_pair(&spread) > 4.);
+}
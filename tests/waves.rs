@@ -0,0 +1,182 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_hell::{
+    enemy::{
+        kinds::{EnemyKind, EnemyKinds},
+        waves::{Formation, Ramp, Wave, WaveSchedule, Waves},
+    },
+    launch::LaunchOptions,
+    map::definition::MapDefinition,
+    rng::{GameRng, RngStream},
+    Enemy,
+};
+use common::*;
+
+fn steady(value: f32) -> Ramp {
+    Ramp {
+        start: value,
+        per_minute: 0.,
+        min: value,
+        max: f32::INFINITY,
+    }
+}
+
+fn wave(formation: Formation) -> Wave {
+    Wave {
+        from: 0.,
+        until: None,
+        interval: steady(0.25),
+        min_count: steady(4.),
+        max_count: steady(4.),
+        kinds: Vec::new(),
+        formation,
+    }
+}
+
+#[test]
+fn shipped_schedule_is_the_default_curve() {
+    let app = test_app();
+    let waves = app.world().resource::<Waves>();
+    let schedules = app.world().resource::<Assets<WaveSchedule>>();
+
+    assert_eq!(schedules.get(&waves.handle), Some(&WaveSchedule::default()));
+    assert_eq!(waves.schedule, WaveSchedule::default());
+}
+
+#[test]
+fn default_curve_matches_the_old_formula() {
+    let schedule = WaveSchedule::default();
+    let wave = &schedule.waves[0];
+    let mut rng = GameRng::new(SEED);
+    let rng = rng.stream(RngStream::EnemySpawn);
+
+    for elapsed in [0_f64, 10., 45., 60., 75.5, 119., 200., 600.] {
+        let interval = (1. - elapsed / 120.).max(0.1) as f32;
+        assert!((wave.interval(elapsed) - interval).abs() < 1e-6);
+
+        let min = (elapsed / 60.).ceil() as i32;
+        let max = ((elapsed / 30.).ceil() as i32).max(min + 1);
+        for _ in 0..20 {
+            assert!((min..max).contains(&wave.count(elapsed, rng)));
+        }
+    }
+}
+
+#[test]
+fn formations_lay_out_their_enemies() {
+    let map = MapDefinition::default();
+    let mut rng = GameRng::new(SEED);
+    let rng = rng.stream(RngStream::EnemySpawn);
+    let player = Vec2::new(100., 0.);
+
+    let ring = wave(Formation::Encircle { radius: 300. }).positions(8, &map, rng, player);
+    assert_eq!(ring.len(), 8);
+    assert!(ring
+        .iter()
+        .all(|position| (position.distance(player) - 300.).abs() < 1e-2));
+
+    let cluster =
+        wave(Formation::Cluster { radius: 50. }).positions(8, &map, rng, player);
+    assert!(cluster
+        .iter()
+        .all(|a| cluster.iter().all(|b| a.distance(*b) <= 100.)));
+    assert!(cluster
+        .iter()
+        .all(|position| position.distance(player) >= 900.));
+}
+
+#[test]
+fn schedule_edits_apply_while_playing() {
+    let mut app = test_app_with(LaunchOptions {
+        god: true,
+        ..default()
+    });
+    let handle = app.world().resource::<Waves>().handle.clone();
+    let mut schedules = app.world_mut().resource_mut::<Assets<WaveSchedule>>();
+    let schedule = schedules.get_mut(&handle).unwrap();
+    // Runners only, for the first second.
+    let mut runners = wave(Formation::Encircle { radius: 600. });
+    runners.until = Some(1.);
+    runners.kinds = vec![("runner".into(), 1), ("nobody".into(), 5)];
+    // A wave that has not started yet.
+    let mut later = wave(Formation::Scattered);
+    later.from = 1000.;
+    schedule.waves = vec![runners, later];
+
+    advance(&mut app, 180);
+
+    let runner = app
+        .world()
+        .resource::<EnemyKinds>()
+        .registry
+        .find("runner")
+        .unwrap();
+    let kinds: Vec<EnemyKind> = app
+        .world_mut()
+        .query_filtered::<&EnemyKind, With<Enemy>>()
+        .iter(app.world())
+        .copied()
+        .collect();
+    // Four every quarter second until the wave closes.
+    assert!(kinds.len() >= 12 && kinds.len() <= 16, "{}", kinds.len());
+    assert!(kinds.iter().all(|kind| *kind == runner));
+}
+
+#[test]
+fn invalid_schedules_are_ignored() {
+    let mut app = test_app_with(LaunchOptions {
+        god: true,
+        ..default()
+    });
+    let handle = app.world().resource::<Waves>().handle.clone();
+    let breakages: [fn(&mut Wave); 8] = [
+        // An interval falling to zero.
+        |wave| {
+            wave.interval.per_minute = -600.;
+            wave.interval.min = -1.;
+        },
+        |wave| {
+            wave.max_count.min = 3.;
+            wave.max_count.max = 2.;
+        },
+        |wave| wave.min_count.start = f32::INFINITY,
+        |wave| wave.interval.per_minute = f32::NAN,
+        |wave| wave.from = f32::NAN,
+        |wave| wave.until = Some(f32::INFINITY),
+        |wave| wave.formation = Formation::Cluster { radius: -1. },
+        |wave| wave.formation = Formation::Encircle { radius: f32::NAN },
+    ];
+    for breakage in breakages {
+        let mut broken = wave(Formation::Scattered);
+        breakage(&mut broken);
+        let schedule = WaveSchedule {
+            waves: vec![broken],
+            bosses: Vec::new(),
+        };
+        assert!(schedule.validate().is_err());
+        app.world_mut()
+            .resource_mut::<Assets<WaveSchedule>>()
+            .insert(&handle, schedule);
+
+        advance(&mut app, 120);
+
+        assert_eq!(
+            app.world().resource::<Waves>().schedule,
+            WaveSchedule::default()
+        );
+    }
+}
+
+#[test]
+fn huge_ramps_are_capped() {
+    let mut huge = wave(Formation::Scattered);
+    huge.interval = steady(1e30);
+    huge.min_count = steady(1e30);
+    huge.max_count = steady(f32::MAX);
+    let mut rng = GameRng::new(SEED);
+    let rng = rng.stream(RngStream::EnemySpawn);
+
+    assert!(huge.interval(0.) <= 3600.);
+    assert_eq!(huge.count(0., rng), 1000);
+}